@@ -1,8 +1,12 @@
-use std::time::Duration;
-
 use cgmath::Vector3;
 use criterion::{Criterion, criterion_group, criterion_main};
-use rustcraft::{core::chunk::Chunk, world::World};
+use rustcraft::{
+    core::{
+        chunk::Chunk,
+        meshing::{ChunkSnapshot, reference_mesher::ReferenceMesher},
+    },
+    world::World,
+};
 
 pub fn single(c: &mut Criterion) {
     let chunk = Chunk::terrain_gen(Vector3::new(0, 0, 0), 6969);
@@ -10,21 +14,29 @@ pub fn single(c: &mut Criterion) {
     for x in -1..=1 {
         for y in -1..=1 {
             for z in -1..=1 {
-                world.need_to_load.push_back((x, y, z));
+                world.need_to_load.push((x, y, z));
             }
         }
     }
-    world.load_new(Duration::from_secs(1000));
+    world.finish_loading();
 
     c.bench_function("Generating mesh of single chunk 32", |b| {
         b.iter(|| {
-            let chunk_copy = Chunk {
-                blocks: chunk.blocks.clone(),
-                ..chunk
-            };
+            let chunk_copy = chunk.clone();
             let _ = chunk_copy.generate_mesh(&world);
         })
     });
+
+    // voxel by voxel mesher the bitmask one replaced, same output
+    c.bench_function(
+        "Generating mesh of single chunk 32 with reference mesher",
+        |b| {
+            b.iter(|| {
+                let chunk_copy = chunk.clone();
+                let _ = ReferenceMesher::build_mesh(&ChunkSnapshot::capture(&chunk_copy, &world));
+            })
+        },
+    );
 }
 
 criterion_group!(benches, single);
@@ -0,0 +1,110 @@
+use std::{cmp::Reverse, collections::VecDeque, time::Duration};
+
+use cgmath::{InnerSpace, Vector3};
+use criterion::{Criterion, criterion_group, criterion_main};
+use hashbrown::HashMap;
+use rustcraft::{
+    core::chunk::Chunk,
+    world::{
+        load_scheduler::{LoadScheduler, LoadView},
+        settings::WorldSettings,
+    },
+};
+
+const STEPS: i64 = 32;
+
+fn view_at(x: i64) -> [LoadView; 1] {
+    let settings = WorldSettings::default();
+    [LoadView::new(
+        Vector3::new(x, 0, 0),
+        Vector3::new(1.0, 0.0, 0.0),
+        settings.load_distance,
+        settings.load_distance_z,
+    )]
+}
+
+/// `World::collect_around` and its priority as they were before the scheduler replaced them:
+/// every position of the load box checked against the queue with `VecDeque::contains`,
+/// then the whole queue sorted and rebuilt.
+struct CollectAround {
+    chunks: HashMap<(i64, i64, i64), Chunk>,
+    need_to_load: VecDeque<(i64, i64, i64)>,
+    load_distance: i64,
+    load_distance_z: i64,
+}
+
+impl CollectAround {
+    fn collect_around(&mut self, player_chunk: Vector3<i64>, camera_dir: Vector3<f32>) {
+        let mut chunks_to_load = Vec::new();
+        for x in -self.load_distance..=self.load_distance {
+            for y in -self.load_distance..=self.load_distance {
+                for z in -self.load_distance_z..=self.load_distance_z {
+                    let chunk_pos = player_chunk + Vector3::new(x, y, z);
+                    let key = (chunk_pos.x, chunk_pos.y, chunk_pos.z);
+
+                    if !self.chunks.contains_key(&key) && !self.need_to_load.contains(&key) {
+                        let priority =
+                            Self::calculate_loading_priority(chunk_pos, player_chunk, camera_dir);
+                        chunks_to_load.push((priority, key));
+                    }
+                }
+            }
+        }
+        chunks_to_load.sort_by_key(|(priority, _)| Reverse(*priority));
+        self.need_to_load = chunks_to_load.into_iter().map(|(_, key)| key).collect();
+    }
+
+    fn calculate_loading_priority(
+        chunk_pos: Vector3<i64>,
+        player_chunk: Vector3<i64>,
+        camera_dir: Vector3<f32>,
+    ) -> u32 {
+        let delta = chunk_pos - player_chunk;
+        let distance_sq = delta.x * delta.x + delta.y * delta.y + delta.z * delta.z;
+        let mut priority: u32 = (2000 - distance_sq).max(0) as u32;
+        let chunk_dir = Vector3::new(delta.x as f32, delta.y as f32, delta.z as f32).normalize();
+        priority += (1000.0 * camera_dir.dot(chunk_dir).max(0.0)) as u32;
+        if delta.x.abs() <= 5 && delta.z.abs() <= 5 && delta.y.abs() <= 5 {
+            priority += 1000;
+        }
+        priority
+    }
+}
+
+pub fn collect_around(c: &mut Criterion) {
+    let settings = WorldSettings::default();
+    let mut old = CollectAround {
+        chunks: HashMap::new(),
+        need_to_load: VecDeque::new(),
+        load_distance: settings.load_distance,
+        load_distance_z: settings.load_distance_z,
+    };
+    c.bench_function("Walking 32 chunks with collect_around", |b| {
+        b.iter(|| {
+            old.need_to_load.clear();
+            for step in 0..STEPS {
+                old.collect_around(Vector3::new(step, 0, 0), Vector3::new(1.0, 0.0, 0.0));
+            }
+        })
+    });
+}
+
+pub fn incremental(c: &mut Criterion) {
+    let mut scheduler = LoadScheduler::new();
+    c.bench_function("Walking 32 chunks with incremental scheduler", |b| {
+        b.iter(|| {
+            scheduler.rescan(&view_at(0), |_| false);
+            for step in 1..STEPS {
+                scheduler.update(&view_at(step), |_| false);
+            }
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    // the quadratic baseline takes seconds per walk
+    config = Criterion::default().sample_size(10).measurement_time(Duration::from_secs(30));
+    targets = collect_around, incremental
+}
+criterion_main!(benches);
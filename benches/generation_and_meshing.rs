@@ -1,5 +1,3 @@
-use std::time::Duration;
-
 use criterion::{Criterion, criterion_group, criterion_main};
 use rustcraft::world::World;
 
@@ -7,8 +5,8 @@ pub fn single(c: &mut Criterion) {
     let mut world = World::new(6969);
     c.bench_function("Generating and meshing single chunk 32", |b| {
         b.iter(|| {
-            world.need_to_load.push_back((0, 0, 0));
-            world.load_new(Duration::from_secs(100));
+            world.need_to_load.push((0, 0, 0));
+            world.finish_loading();
             world.chunks.get(&(0, 0, 0)).unwrap().generate_mesh(&world);
             world.chunks.clear();
         })
@@ -28,9 +26,9 @@ pub fn thousand(c: &mut Criterion) {
     c.bench_function("Generating and meshing of 5x5x5 chunks 32", |b| {
         b.iter(|| {
             for key in &keys {
-                world.need_to_load.push_back(*key);
+                world.need_to_load.push(*key);
             }
-            world.load_new(Duration::from_secs(1000));
+            world.finish_loading();
             for key in &keys {
                 world.chunks.get(key).unwrap().generate_mesh(&world);
             }
@@ -1,6 +1,6 @@
 use futures::executor::block_on;
 use rand::RngCore;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
 use winit::event::{ElementState, MouseButton, WindowEvent};
@@ -9,7 +9,7 @@ use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::{CursorGrabMode, Window, WindowAttributes};
 
 use crate::core::render::renderer::Renderer;
-use crate::world::World;
+use crate::world::{World, observer::Observer};
 
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
@@ -18,6 +18,8 @@ use std::sync::Mutex;
 static WINDOW_PTR: Lazy<Mutex<Option<&'static winit::window::Window>>> =
     Lazy::new(|| Mutex::new(None));
 
+const LOAD_DISTANCE_STEP: i64 = 2;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct MovementState {
@@ -74,6 +76,15 @@ impl App {
     }
 }
 
+fn change_load_distance(world: &mut World, renderer: &mut Renderer, delta: i64) {
+    let settings = world
+        .settings
+        .with_load_distance(world.settings.load_distance + delta);
+    world.set_settings(settings);
+    renderer.set_far_plane(world.settings.far_plane());
+    log::info!("load distance set to {}", world.settings.load_distance);
+}
+
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if self.renderer.is_none() {
@@ -85,7 +96,8 @@ impl ApplicationHandler for App {
             let window = event_loop.create_window(window_attributes).unwrap();
             let window_ref: &'static Window = Box::leak(Box::new(window));
             *WINDOW_PTR.lock().unwrap() = Some(window_ref);
-            let renderer = block_on(Renderer::new(window_ref));
+            let mut renderer = block_on(Renderer::new(window_ref));
+            renderer.set_far_plane(self.world.settings.far_plane());
 
             self.renderer = Some(renderer);
         }
@@ -128,62 +140,68 @@ impl ApplicationHandler for App {
                         KeyCode::ShiftLeft => {
                             self.movement.down = event.state == ElementState::Pressed
                         }
+                        KeyCode::Equal if event.state == ElementState::Pressed => {
+                            change_load_distance(&mut self.world, renderer, LOAD_DISTANCE_STEP)
+                        }
+                        KeyCode::Minus if event.state == ElementState::Pressed => {
+                            change_load_distance(&mut self.world, renderer, -LOAD_DISTANCE_STEP)
+                        }
                         _ => {}
                     }
                 }
             }
-            WindowEvent::MouseInput { state, button, .. } => {
-                if button == MouseButton::Left {
-                    if state == ElementState::Pressed {
-                        let _ = window.set_cursor_grab(CursorGrabMode::Locked);
-                        window.set_cursor_visible(false);
-                        self.mouse_locked = true;
-                    } else {
-                        let _ = window.set_cursor_grab(CursorGrabMode::None);
-                        window.set_cursor_visible(true);
-                        self.mouse_locked = false;
-                        self.last_mouse_pos = (f32::NAN, f32::NAN);
-                    }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                if state == ElementState::Pressed {
+                    let _ = window.set_cursor_grab(CursorGrabMode::Locked);
+                    window.set_cursor_visible(false);
+                    self.mouse_locked = true;
+                } else {
+                    let _ = window.set_cursor_grab(CursorGrabMode::None);
+                    window.set_cursor_visible(true);
+                    self.mouse_locked = false;
+                    self.last_mouse_pos = (f32::NAN, f32::NAN);
                 }
             }
-            WindowEvent::CursorMoved { position, .. } => {
-                if self.mouse_locked {
-                    if self.last_mouse_pos.1.is_nan() {
-                        self.last_mouse_pos = (position.x as f32, position.y as f32);
-                    };
-                    let delta = (
-                        position.x as f32 - self.last_mouse_pos.0,
-                        position.y as f32 - self.last_mouse_pos.1,
-                    );
-                    renderer.update_camera(
-                        0.016,
-                        (
-                            if self.movement.forward {
-                                1.0
-                            } else if self.movement.backward {
-                                -1.0
-                            } else {
-                                0.0
-                            },
-                            if self.movement.right {
-                                1.0
-                            } else if self.movement.left {
-                                -1.0
-                            } else {
-                                0.0
-                            },
-                            if self.movement.up {
-                                1.0
-                            } else if self.movement.down {
-                                -1.0
-                            } else {
-                                0.0
-                            },
-                        ),
-                        delta,
-                    );
+            WindowEvent::CursorMoved { position, .. } if self.mouse_locked => {
+                if self.last_mouse_pos.1.is_nan() {
                     self.last_mouse_pos = (position.x as f32, position.y as f32);
-                }
+                };
+                let delta = (
+                    position.x as f32 - self.last_mouse_pos.0,
+                    position.y as f32 - self.last_mouse_pos.1,
+                );
+                renderer.update_camera(
+                    0.016,
+                    (
+                        if self.movement.forward {
+                            1.0
+                        } else if self.movement.backward {
+                            -1.0
+                        } else {
+                            0.0
+                        },
+                        if self.movement.right {
+                            1.0
+                        } else if self.movement.left {
+                            -1.0
+                        } else {
+                            0.0
+                        },
+                        if self.movement.up {
+                            1.0
+                        } else if self.movement.down {
+                            -1.0
+                        } else {
+                            0.0
+                        },
+                    ),
+                    delta,
+                );
+                self.last_mouse_pos = (position.x as f32, position.y as f32);
             }
             WindowEvent::Resized(size) => {
                 renderer.resize(size);
@@ -196,8 +214,9 @@ impl ApplicationHandler for App {
                 let delta_time = now.duration_since(self.last_time).as_secs_f64();
                 self.last_time = now;
                 let renderer = self.renderer.as_mut().unwrap();
-                self.world
-                    .update(Duration::from_secs_f32(0.2 / 60.0), renderer);
+                let observer = Observer::from_camera(&renderer.camera, &self.world.settings);
+                let frustum = renderer.camera.frustum.clone();
+                self.world.update(&[observer], Some(&frustum), renderer);
                 renderer.update_camera(
                     delta_time,
                     (
@@ -226,7 +245,7 @@ impl ApplicationHandler for App {
                     (0.0, 0.0),
                 );
                 // renderer.new_render(&self.world);
-                match renderer.render(&self.world) {
+                match renderer.render(&mut self.world) {
                     Ok(_) => {}
                     Err(wgpu::SurfaceError::Lost) => renderer.resize(window.inner_size()),
                     Err(wgpu::SurfaceError::OutOfMemory) => {
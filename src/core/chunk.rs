@@ -5,16 +5,21 @@ use cgmath::Vector3;
 use crate::{
     core::{
         block::Block,
-        meshing::{GreedyMesher, Vertex},
+        chunk_state::{ChunkState, InvalidTransition},
+        meshing::{ChunkSnapshot, Lod, Vertex},
     },
     world::{TerrainGenerator, World},
 };
 
+#[derive(Clone)]
 pub struct Chunk {
     pub blocks: [Block; CHUNK_VOLUME],
     pub _pos: Vector3<i64>,
-    pub is_rendered: bool,
-    pub is_dirty: bool,
+    state: ChunkState,
+    /// visibility frame this chunk was last inside the frustum, for LRU eviction
+    pub last_visible: u64,
+    /// resolution the chunk is meshed at, picked by the world from the observer distance
+    pub lod: Lod,
 }
 
 #[allow(dead_code)]
@@ -24,8 +29,9 @@ impl Chunk {
         Chunk {
             blocks,
             _pos: pos,
-            is_rendered: true,
-            is_dirty: true,
+            state: ChunkState::Generated,
+            last_visible: 0,
+            lod: Lod::FULL,
         }
     }
 
@@ -35,22 +41,41 @@ impl Chunk {
         Chunk {
             blocks,
             _pos: world_pos,
-            is_rendered: true,
-            is_dirty: true,
+            state: ChunkState::Generated,
+            last_visible: 0,
+            lod: Lod::FULL,
         }
     }
 
-    pub fn generate_mesh(&self, world: &World) -> (Vec<Vertex>, Vec<u32>) {
-        if !self.is_rendered {
-            return (Vec::new(), Vec::new());
-        }
-        let (mut vertices, indices) = GreedyMesher::build_mesh(self, world);
-        for v in &mut vertices {
-            v.pos[0] += self._pos.x as f32 * CHUNK_SIZE as f32;
-            v.pos[1] += self._pos.y as f32 * CHUNK_SIZE as f32;
-            v.pos[2] += self._pos.z as f32 * CHUNK_SIZE as f32;
+    pub fn state(&self) -> ChunkState {
+        self.state
+    }
+
+    pub fn transition(&mut self, next: ChunkState) -> Result<(), InvalidTransition> {
+        if !self.state.can_transition_to(next) {
+            return Err(InvalidTransition {
+                from: self.state,
+                to: next,
+            });
         }
-        (vertices, indices)
+        self.state = next;
+        Ok(())
+    }
+
+    pub const fn memory_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    /// Chunk has all its data and can be (re)meshed.
+    pub fn is_meshable(&self) -> bool {
+        matches!(self.state, ChunkState::Lit | ChunkState::Ready)
+    }
+
+    /// Chunk-local vertices at the chunk's `lod` from the world's mesher, the renderer offsets
+    /// them by the chunk origin per draw.
+    pub fn generate_mesh(&self, world: &World) -> Vec<Vertex> {
+        let snapshot = ChunkSnapshot::capture(self, world);
+        world.settings.mesher.mesher().mesh(&snapshot)
     }
 
     #[inline(always)]
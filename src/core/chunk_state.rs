@@ -0,0 +1,61 @@
+use std::fmt;
+
+/// Lifecycle of a chunk position, in the order a chunk normally walks through it.
+/// `Queued` and `Generating` are tracked by the loader before a `Chunk` exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ChunkState {
+    Queued,
+    Generating,
+    Generated,
+    Decorated,
+    Lit,
+    Meshing,
+    Ready,
+    Unloading,
+}
+
+impl ChunkState {
+    pub const ALL: [ChunkState; 8] = [
+        ChunkState::Queued,
+        ChunkState::Generating,
+        ChunkState::Generated,
+        ChunkState::Decorated,
+        ChunkState::Lit,
+        ChunkState::Meshing,
+        ChunkState::Ready,
+        ChunkState::Unloading,
+    ];
+
+    pub fn can_transition_to(self, next: ChunkState) -> bool {
+        use ChunkState::*;
+        matches!(
+            (self, next),
+            (Queued, Generating)
+                | (Generating, Generated)
+                | (Generated, Decorated)
+                | (Decorated, Lit)
+                | (Lit, Meshing)
+                | (Meshing, Ready)
+                // edits and neighbour changes send a finished chunk back to the mesher
+                | (Ready, Meshing)
+        ) || (next == Unloading && self != Unloading)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub from: ChunkState,
+    pub to: ChunkState,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid chunk transition {:?} -> {:?}",
+            self.from, self.to
+        )
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
@@ -0,0 +1,52 @@
+use crate::core::meshing::{
+    ChunkSnapshot, GreedyMesher, NaiveMesher, SmoothMesher, Vertex,
+    reference_mesher::ReferenceMesher,
+};
+
+/// Turns a chunk snapshot into chunk-local vertices. Meshers run on the mesh workers and
+/// never see the world, only the snapshot and its one block border.
+///
+/// Output is quads of four vertices drawn with the shared `QUAD_INDICES`, grouped by face
+/// direction in `Vertex::normal` order, the renderer culls each group as one index range.
+pub trait ChunkMesher: Send + Sync {
+    fn mesh(&self, snapshot: &ChunkSnapshot) -> Vec<Vertex>;
+
+    /// Whether every quad lies in a block face plane of its direction, only then can the
+    /// renderer cull a direction group from the chunk bounds alone.
+    fn axis_aligned(&self) -> bool {
+        true
+    }
+}
+
+impl ChunkMesher for GreedyMesher {
+    fn mesh(&self, snapshot: &ChunkSnapshot) -> Vec<Vertex> {
+        Self::build_mesh(snapshot)
+    }
+}
+
+impl ChunkMesher for ReferenceMesher {
+    fn mesh(&self, snapshot: &ChunkSnapshot) -> Vec<Vertex> {
+        Self::build_mesh(snapshot)
+    }
+}
+
+/// Mesher a world builds its chunks with, set through `WorldSettings::with_mesher`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MesherKind {
+    /// one quad per visible face, the correctness reference
+    Naive,
+    #[default]
+    Greedy,
+    /// surface nets over block opacity, for smooth terrain
+    Smooth,
+}
+
+impl MesherKind {
+    pub fn mesher(self) -> &'static dyn ChunkMesher {
+        match self {
+            Self::Naive => &NaiveMesher,
+            Self::Greedy => &GreedyMesher,
+            Self::Smooth => &SmoothMesher,
+        }
+    }
+}
@@ -1,176 +1,162 @@
 use crate::core::meshing::Vertex;
 use cgmath::Vector3;
 
+/// Quad of `width` × `height` blocks around `pos`, in the vertex order `QUAD_INDICES` expects.
+/// Texture coordinates count blocks, the shader repeats the texture once per block from its
+/// array layer or atlas rect.
 pub fn generate_face(
     pos: Vector3<f32>,
     normal: Vector3<f32>,
     tex_id: u32,
     width: f32,
     height: f32,
-) -> (Vec<Vertex>, Vec<u32>) {
-    const ATLAS_WIDTH: f32 = 16.0;
-    const ATLAS_HEIGHT: f32 = 16.0;
-    const TEXTURE_SIZE: f32 = 16.0;
-    const TEXTURES_PER_ROW: u16 = (ATLAS_WIDTH / TEXTURE_SIZE) as u16;
-    const TEXTURES_PER_COL: u16 = (ATLAS_HEIGHT / TEXTURE_SIZE) as u16;
-    const TEX_SIZE_U: f32 = TEXTURE_SIZE / ATLAS_WIDTH;
-    const TEX_SIZE_V: f32 = TEXTURE_SIZE / ATLAS_HEIGHT;
+) -> Vec<Vertex> {
     const GAP: f32 = 0.0;
 
     let half_width = width * 0.5 - GAP;
     let half_height = height * 0.5 - GAP;
 
     let uvs = [[0f32, 0f32], [width, 0f32], [width, height], [0f32, height]];
+    let normal_index = match normal {
+        Vector3 { x: 1.0, .. } => 0,
+        Vector3 { x: -1.0, .. } => 1,
+        Vector3 { y: 1.0, .. } => 2,
+        Vector3 { y: -1.0, .. } => 3,
+        Vector3 { z: 1.0, .. } => 4,
+        _ => 5,
+    };
+    // `pos` is chunk-local, corners land on half blocks and are stored shifted by half a block
+    let vertex = |corner: [f32; 3], uv: [f32; 2]| {
+        Vertex::new(
+            corner.map(|c| (c + 0.5).round() as u32),
+            uv.map(|c| c as u32),
+            normal_index,
+            tex_id,
+            Vertex::UNOCCLUDED,
+        )
+    };
 
-    let vertices = match normal {
+    match normal {
         Vector3 { x: 1.0, .. } => {
             vec![
-                Vertex {
-                    pos: [pos.x + 0.5, pos.y - half_width, pos.z - half_height],
-                    tex_coord: uvs[3],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + 0.5, pos.y + half_width, pos.z - half_height],
-                    tex_coord: uvs[2],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + 0.5, pos.y + half_width, pos.z + half_height],
-                    tex_coord: uvs[1],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + 0.5, pos.y - half_width, pos.z + half_height],
-                    tex_coord: uvs[0],
-                    tex_id,
-                },
+                vertex(
+                    [pos.x + 0.5, pos.y - half_width, pos.z - half_height],
+                    uvs[3],
+                ),
+                vertex(
+                    [pos.x + 0.5, pos.y + half_width, pos.z - half_height],
+                    uvs[2],
+                ),
+                vertex(
+                    [pos.x + 0.5, pos.y + half_width, pos.z + half_height],
+                    uvs[1],
+                ),
+                vertex(
+                    [pos.x + 0.5, pos.y - half_width, pos.z + half_height],
+                    uvs[0],
+                ),
             ]
         }
         Vector3 { x: -1.0, .. } => {
             vec![
-                Vertex {
-                    pos: [pos.x - 0.5, pos.y + half_width, pos.z - half_height],
-                    tex_coord: uvs[3],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - 0.5, pos.y - half_width, pos.z - half_height],
-                    tex_coord: uvs[2],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - 0.5, pos.y - half_width, pos.z + half_height],
-                    tex_coord: uvs[1],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - 0.5, pos.y + half_width, pos.z + half_height],
-                    tex_coord: uvs[0],
-                    tex_id,
-                },
+                vertex(
+                    [pos.x - 0.5, pos.y + half_width, pos.z - half_height],
+                    uvs[3],
+                ),
+                vertex(
+                    [pos.x - 0.5, pos.y - half_width, pos.z - half_height],
+                    uvs[2],
+                ),
+                vertex(
+                    [pos.x - 0.5, pos.y - half_width, pos.z + half_height],
+                    uvs[1],
+                ),
+                vertex(
+                    [pos.x - 0.5, pos.y + half_width, pos.z + half_height],
+                    uvs[0],
+                ),
             ]
         }
         Vector3 { y: 1.0, .. } => {
             vec![
-                Vertex {
-                    pos: [pos.x + half_width, pos.y + 0.5, pos.z - half_height],
-                    tex_coord: uvs[2],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y + 0.5, pos.z - half_height],
-                    tex_coord: uvs[3],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y + 0.5, pos.z + half_height],
-                    tex_coord: uvs[0],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + half_width, pos.y + 0.5, pos.z + half_height],
-                    tex_coord: uvs[1],
-                    tex_id,
-                },
+                vertex(
+                    [pos.x + half_width, pos.y + 0.5, pos.z - half_height],
+                    uvs[2],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y + 0.5, pos.z - half_height],
+                    uvs[3],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y + 0.5, pos.z + half_height],
+                    uvs[0],
+                ),
+                vertex(
+                    [pos.x + half_width, pos.y + 0.5, pos.z + half_height],
+                    uvs[1],
+                ),
             ]
         }
         Vector3 { y: -1.0, .. } => {
             vec![
-                Vertex {
-                    pos: [pos.x + half_width, pos.y - 0.5, pos.z + half_height],
-                    tex_coord: uvs[1],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y - 0.5, pos.z + half_height],
-                    tex_coord: uvs[0],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y - 0.5, pos.z - half_height],
-                    tex_coord: uvs[3],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + half_width, pos.y - 0.5, pos.z - half_height],
-                    tex_coord: uvs[2],
-                    tex_id,
-                },
+                vertex(
+                    [pos.x + half_width, pos.y - 0.5, pos.z + half_height],
+                    uvs[1],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y - 0.5, pos.z + half_height],
+                    uvs[0],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y - 0.5, pos.z - half_height],
+                    uvs[3],
+                ),
+                vertex(
+                    [pos.x + half_width, pos.y - 0.5, pos.z - half_height],
+                    uvs[2],
+                ),
             ]
         }
         Vector3 { z: 1.0, .. } => {
             vec![
-                Vertex {
-                    pos: [pos.x - half_width, pos.y - half_height, pos.z + 0.5],
-                    tex_coord: uvs[1],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + half_width, pos.y - half_height, pos.z + 0.5],
-                    tex_coord: uvs[0],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + half_width, pos.y + half_height, pos.z + 0.5],
-                    tex_coord: uvs[3],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y + half_height, pos.z + 0.5],
-                    tex_coord: uvs[2],
-                    tex_id,
-                },
+                vertex(
+                    [pos.x - half_width, pos.y - half_height, pos.z + 0.5],
+                    uvs[1],
+                ),
+                vertex(
+                    [pos.x + half_width, pos.y - half_height, pos.z + 0.5],
+                    uvs[0],
+                ),
+                vertex(
+                    [pos.x + half_width, pos.y + half_height, pos.z + 0.5],
+                    uvs[3],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y + half_height, pos.z + 0.5],
+                    uvs[2],
+                ),
             ]
         }
         Vector3 { z: -1.0, .. } => {
             vec![
-                Vertex {
-                    pos: [pos.x + half_width, pos.y - half_height, pos.z - 0.5],
-                    tex_coord: uvs[1],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y - half_height, pos.z - 0.5],
-                    tex_coord: uvs[0],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x - half_width, pos.y + half_height, pos.z - 0.5],
-                    tex_coord: uvs[3],
-                    tex_id,
-                },
-                Vertex {
-                    pos: [pos.x + half_width, pos.y + half_height, pos.z - 0.5],
-                    tex_coord: uvs[2],
-                    tex_id,
-                },
+                vertex(
+                    [pos.x + half_width, pos.y - half_height, pos.z - 0.5],
+                    uvs[1],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y - half_height, pos.z - 0.5],
+                    uvs[0],
+                ),
+                vertex(
+                    [pos.x - half_width, pos.y + half_height, pos.z - 0.5],
+                    uvs[3],
+                ),
+                vertex(
+                    [pos.x + half_width, pos.y + half_height, pos.z - 0.5],
+                    uvs[2],
+                ),
             ]
         }
         _ => Vec::new(),
-    };
-
-    let indices = vec![0, 1, 2, 2, 3, 0];
-
-    (vertices, indices)
+    }
 }
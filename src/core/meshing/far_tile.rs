@@ -0,0 +1,160 @@
+use cgmath::{InnerSpace, Vector3};
+
+use crate::{core::chunk::CHUNK_SIZE, world::TerrainGenerator};
+
+/// Chunk columns along each edge of a far terrain tile.
+pub const FAR_TILE_CHUNKS: i64 = 32;
+pub const FAR_TILE_BLOCKS: i64 = FAR_TILE_CHUNKS * CHUNK_SIZE as i64;
+
+// the impostor sits this far below the sampled surface, so real chunks win where both overlap
+const SINK: f32 = 4.0;
+// hides cracks towards neighbouring tiles of another resolution
+const SKIRT_DEPTH: f32 = 64.0;
+const SUN: Vector3<f32> = Vector3::new(0.3, 0.2, 0.93);
+const GRASS: [f32; 3] = [0.38, 0.62, 0.24];
+const STONE: [f32; 3] = [0.5, 0.5, 0.52];
+// slopes steeper than this show stone
+const STEEP_NORMAL_Z: f32 = 0.75;
+
+/// Vertex of the far terrain, placed in world space with its shading baked in.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FarVertex {
+    pub position: [f32; 3],
+    pub color: [u8; 4],
+}
+
+impl FarVertex {
+    pub const fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Heightmap mesh of `FAR_TILE_CHUNKS`² chunk columns, sampled straight from the
+/// generator's 2D height without generating any chunk.
+pub struct FarTile {
+    /// in tiles
+    pub key: (i64, i64),
+    /// quads along each edge
+    pub grid: usize,
+    pub vertices: Vec<FarVertex>,
+    pub indices: Vec<u32>,
+}
+
+impl FarTile {
+    /// `grid` has to divide `FAR_TILE_BLOCKS`.
+    pub fn build(seed: u32, key: (i64, i64), grid: usize) -> Self {
+        debug_assert!(FAR_TILE_BLOCKS % grid as i64 == 0);
+        let step = FAR_TILE_BLOCKS / grid as i64;
+        let origin = (key.0 * FAR_TILE_BLOCKS, key.1 * FAR_TILE_BLOCKS);
+        // one extra ring of samples around the tile for the normals
+        let side = grid + 3;
+        let points = (0..side).flat_map(|j| {
+            (0..side).map(move |i| {
+                (
+                    origin.0 + (i as i64 - 1) * step,
+                    origin.1 + (j as i64 - 1) * step,
+                )
+            })
+        });
+        let heights: Vec<f32> = TerrainGenerator::surface_heights(seed, points).collect();
+        // sample coordinates are shifted by one against the grid
+        let sample = |i: usize, j: usize| heights[i + j * side];
+
+        let mut vertices = Vec::with_capacity((grid + 1) * (grid + 1) + 8 * (grid + 1));
+        for j in 0..=grid {
+            for i in 0..=grid {
+                let dx = (sample(i + 2, j + 1) - sample(i, j + 1)) / (2 * step) as f32;
+                let dy = (sample(i + 1, j + 2) - sample(i + 1, j)) / (2 * step) as f32;
+                let normal = Vector3::new(-dx, -dy, 1.0).normalize();
+                vertices.push(FarVertex {
+                    position: [
+                        (origin.0 + i as i64 * step) as f32,
+                        (origin.1 + j as i64 * step) as f32,
+                        // surface blocks are centered on integers, their top is half a block lower
+                        sample(i + 1, j + 1).ceil() - 0.5 - SINK,
+                    ],
+                    color: Self::shade(normal),
+                });
+            }
+        }
+
+        let at = |i: usize, j: usize| (i + j * (grid + 1)) as u32;
+        let mut indices = Vec::with_capacity(6 * grid * grid + 48 * grid);
+        for j in 0..grid {
+            for i in 0..grid {
+                let (a, b, c, d) = (at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1));
+                indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+
+        // skirts hang below every edge, both windings so they close a crack from either side
+        let edges: [Vec<u32>; 4] = [
+            (0..=grid).map(|i| at(i, 0)).collect(),
+            (0..=grid).map(|j| at(grid, j)).collect(),
+            (0..=grid).rev().map(|i| at(i, grid)).collect(),
+            (0..=grid).rev().map(|j| at(0, j)).collect(),
+        ];
+        for edge in edges {
+            let base = vertices.len() as u32;
+            for &top in &edge {
+                let mut vertex = vertices[top as usize];
+                vertex.position[2] -= SKIRT_DEPTH;
+                vertices.push(vertex);
+            }
+            for k in 0..edge.len() - 1 {
+                let (a, b) = (edge[k], edge[k + 1]);
+                let (c, d) = (base + k as u32 + 1, base + k as u32);
+                indices.extend_from_slice(&[a, d, c, a, c, b, a, b, c, a, c, d]);
+            }
+        }
+
+        Self {
+            key,
+            grid,
+            vertices,
+            indices,
+        }
+    }
+
+    fn shade(normal: Vector3<f32>) -> [u8; 4] {
+        let base = if normal.z < STEEP_NORMAL_Z {
+            STONE
+        } else {
+            GRASS
+        };
+        let light = 0.55 + 0.45 * normal.dot(SUN.normalize()).max(0.0);
+        let [r, g, b] = base.map(|c| (c * light * 255.0) as u8);
+        [r, g, b, 255]
+    }
+
+    /// World-space xy bounds in blocks, min and max.
+    pub fn bounds(key: (i64, i64)) -> ([f32; 2], [f32; 2]) {
+        let min = [
+            (key.0 * FAR_TILE_BLOCKS) as f32,
+            (key.1 * FAR_TILE_BLOCKS) as f32,
+        ];
+        (
+            min,
+            [
+                min[0] + FAR_TILE_BLOCKS as f32,
+                min[1] + FAR_TILE_BLOCKS as f32,
+            ],
+        )
+    }
+}
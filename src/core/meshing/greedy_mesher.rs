@@ -1,56 +1,75 @@
-use crate::{
-    core::{
-        block::Block,
-        chunk::{CHUNK_SIZE, Chunk},
-        meshing::{Vertex, generate_face},
-    },
-    world::World,
+use crate::core::{
+    block::Block,
+    chunk::CHUNK_SIZE,
+    meshing::{ChunkSnapshot, Vertex, generate_face},
 };
 use cgmath::Vector3;
 
+pub(super) const NORMALS: [Vector3<f32>; 6] = [
+    Vector3::new(1.0, 0.0, 0.0),
+    Vector3::new(-1.0, 0.0, 0.0),
+    Vector3::new(0.0, 1.0, 0.0),
+    Vector3::new(0.0, -1.0, 0.0),
+    Vector3::new(0.0, 0.0, 1.0),
+    Vector3::new(0.0, 0.0, -1.0),
+];
+
+pub(super) const DIRECTIONS: [(i64, i64, i64); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+// one bit per cell of a chunk row, CHUNK_SIZE is exactly 32
+type RowMask = u32;
+// padded column along a normal axis, bit k is the cell at depth k - 1
+type ColumnMask = u64;
+
+/// Bitmask greedy mesher. Opacity is packed into 34-bit columns along each axis, faces fall
+/// out of a shift and an and-not, and each slice is merged row by row with bit scans.
+/// Output matches `ReferenceMesher` exactly, quads are emitted in the same order.
 pub struct GreedyMesher;
 
 impl GreedyMesher {
-    pub fn build_mesh(chunk: &Chunk, world: &World) -> (Vec<Vertex>, Vec<u32>) {
-        if Self::is_only_air_fast(chunk) && Self::is_only_air(chunk) {
-            return (Vec::new(), Vec::new());
+    pub fn build_mesh(snapshot: &ChunkSnapshot) -> Vec<Vertex> {
+        if Self::is_only_air_fast(snapshot) && Self::is_only_air(snapshot) {
+            return Vec::new();
         }
-        let normals = [
-            Vector3::new(1.0, 0.0, 0.0),
-            Vector3::new(-1.0, 0.0, 0.0),
-            Vector3::new(0.0, 1.0, 0.0),
-            Vector3::new(0.0, -1.0, 0.0),
-            Vector3::new(0.0, 0.0, 1.0),
-            Vector3::new(0.0, 0.0, -1.0),
-        ];
-        // Precompute exposed faces for the entire chunk to avoid repeated world lookups
-        let exposed_cache = Self::build_exposed_cache(chunk, world);
-
-        let direction_results: Vec<(Vec<Vertex>, Vec<u32>)> = normals
-            .iter()
-            .enumerate()
-            .map(|(dir, &normal)| Self::greedy_mesh_direction(chunk, normal, dir, &exposed_cache))
-            .collect();
 
         let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-        let mut index_offset = 0u32;
 
-        for (dir_vertices, dir_indices) in direction_results {
-            vertices.extend(dir_vertices);
-            indices.extend(dir_indices.into_iter().map(|i| i + index_offset));
-            index_offset = vertices.len() as u32;
+        for axis in 0..3 {
+            let columns = Self::axis_columns(snapshot, axis);
+            for direction in [axis * 2, axis * 2 + 1] {
+                let normal = NORMALS[direction];
+                let faces = Self::face_masks(&columns, direction % 2 == 0);
+                for (depth, rows) in faces.iter().enumerate() {
+                    if rows.iter().all(|&row| row == 0) {
+                        continue;
+                    }
+                    let slice = Slice {
+                        snapshot,
+                        direction,
+                        normal,
+                        axes: slice_axes(normal),
+                        depth,
+                    };
+                    slice.mesh(*rows, &mut vertices);
+                }
+            }
         }
 
-        (vertices, indices)
+        vertices
     }
 
-    fn is_only_air(chunk: &Chunk) -> bool {
+    pub(super) fn is_only_air(snapshot: &ChunkSnapshot) -> bool {
         for x in 0..CHUNK_SIZE {
             for y in 0..CHUNK_SIZE {
                 for z in 0..CHUNK_SIZE {
-                    let idx = Chunk::index(x, y, z);
-                    if !chunk.blocks[idx].is_transpose() {
+                    if !snapshot.get(x, y, z).is_transpose() {
                         return false;
                     }
                 }
@@ -60,394 +79,297 @@ impl GreedyMesher {
     }
 
     #[inline]
-    fn is_only_air_fast(chunk: &Chunk) -> bool {
-        const IDX_1: usize = Chunk::index(0, 0, 0);
-        const IDX_2: usize = Chunk::index(0, 0, CHUNK_SIZE - 1);
-        const IDX_3: usize = Chunk::index(0, CHUNK_SIZE - 1, 0);
-        const IDX_4: usize = Chunk::index(0, CHUNK_SIZE - 1, CHUNK_SIZE - 1);
-        const IDX_5: usize = Chunk::index(CHUNK_SIZE - 1, 0, 0);
-        const IDX_6: usize = Chunk::index(CHUNK_SIZE - 1, 0, CHUNK_SIZE - 1);
-        const IDX_7: usize = Chunk::index(CHUNK_SIZE - 1, CHUNK_SIZE - 1, 0);
-        const IDX_8: usize = Chunk::index(CHUNK_SIZE - 1, CHUNK_SIZE - 1, CHUNK_SIZE - 1);
-        chunk.blocks[IDX_1].is_transpose()
-            && chunk.blocks[IDX_2].is_transpose()
-            && chunk.blocks[IDX_3].is_transpose()
-            && chunk.blocks[IDX_4].is_transpose()
-            && chunk.blocks[IDX_5].is_transpose()
-            && chunk.blocks[IDX_6].is_transpose()
-            && chunk.blocks[IDX_7].is_transpose()
-            && chunk.blocks[IDX_8].is_transpose()
+    pub(super) fn is_only_air_fast(snapshot: &ChunkSnapshot) -> bool {
+        const LAST: usize = CHUNK_SIZE - 1;
+        [
+            (0, 0, 0),
+            (0, 0, LAST),
+            (0, LAST, 0),
+            (0, LAST, LAST),
+            (LAST, 0, 0),
+            (LAST, 0, LAST),
+            (LAST, LAST, 0),
+            (LAST, LAST, LAST),
+        ]
+        .iter()
+        .all(|&(x, y, z)| snapshot.get(x, y, z).is_transpose())
     }
 
-    fn build_exposed_cache(
-        chunk: &Chunk,
-        world: &World,
-    ) -> [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE] {
-        let mut cache = [[[0u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE];
-        let chunk_world_base = Vector3::new(
-            chunk._pos.x * CHUNK_SIZE as i64,
-            chunk._pos.y * CHUNK_SIZE as i64,
-            chunk._pos.z * CHUNK_SIZE as i64,
-        );
-
-        for x in 0..CHUNK_SIZE {
-            for y in 0..CHUNK_SIZE {
-                for z in 0..CHUNK_SIZE {
-                    let mut exposed_mask = 0u8;
-
-                    let directions = [
-                        (1, 0, 0),
-                        (-1, 0, 0),
-                        (0, 1, 0),
-                        (0, -1, 0),
-                        (0, 0, 1),
-                        (0, 0, -1),
-                    ];
-
-                    for (dir, &(dx, dy, dz)) in directions.iter().enumerate() {
-                        let nx = x as i64 + dx;
-                        let ny = y as i64 + dy;
-                        let nz = z as i64 + dz;
-
-                        let exposed = if nx >= 0
-                            && nx < CHUNK_SIZE as i64
-                            && ny >= 0
-                            && ny < CHUNK_SIZE as i64
-                            && nz >= 0
-                            && nz < CHUNK_SIZE as i64
-                        {
-                            let block = chunk.get(nx as usize, ny as usize, nz as usize);
-                            block.is_transpose()
-                        } else {
-                            let world_pos = Vector3::new(
-                                chunk_world_base.x + nx,
-                                chunk_world_base.y + ny,
-                                chunk_world_base.z + nz,
-                            );
-                            Self::is_face_exposed_new(world, world_pos)
-                        };
-
-                        if exposed {
-                            exposed_mask |= 1 << dir;
-                        }
+    /// Opacity columns along `axis`, indexed by the slice's `[u][v]`.
+    fn axis_columns(
+        snapshot: &ChunkSnapshot,
+        axis: usize,
+    ) -> [[ColumnMask; CHUNK_SIZE]; CHUNK_SIZE] {
+        let mut columns = [[0; CHUNK_SIZE]; CHUNK_SIZE];
+        for (u, row) in columns.iter_mut().enumerate() {
+            for (v, column) in row.iter_mut().enumerate() {
+                for depth in -1..=CHUNK_SIZE as i64 {
+                    if snapshot.is_opaque(slice_cell(axis, u as i64, v as i64, depth)) {
+                        *column |= 1 << (depth + 1);
                     }
-
-                    cache[x][y][z] = exposed_mask;
                 }
             }
         }
-
-        cache
-    }
-
-    fn is_face_exposed_new(world: &World, pos: Vector3<i64>) -> bool {
-        if let Some(chunk) = world.get_chunk(&pos) {
-            let block = chunk.get_from_world_pos(pos);
-            block.is_transpose()
-        } else {
-            false
-        }
+        columns
     }
 
-    fn greedy_mesh_direction(
-        chunk: &Chunk,
-        normal: Vector3<f32>,
-        direction: usize,
-        exposed_cache: &[[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
-    ) -> (Vec<Vertex>, Vec<u32>) {
-        let mut vertices = Vec::with_capacity(1024);
-        let mut indices = Vec::with_capacity(1024);
-        let mut index_offset = 0u32;
-
-        let mut visited = BitSet::new(CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE);
-
-        let (u_axis, v_axis, depth_axis) = if normal.x.abs() > 0.5 {
-            (
-                Vector3::new(0.0, 1.0, 0.0),
-                Vector3::new(0.0, 0.0, 1.0),
-                Vector3::new(1.0, 0.0, 0.0),
-            )
-        } else if normal.y.abs() > 0.5 {
-            (
-                Vector3::new(1.0, 0.0, 0.0),
-                Vector3::new(0.0, 0.0, 1.0),
-                Vector3::new(0.0, 1.0, 0.0),
-            )
-        } else {
-            (
-                Vector3::new(1.0, 0.0, 0.0),
-                Vector3::new(0.0, 1.0, 0.0),
-                Vector3::new(0.0, 0.0, 1.0),
-            )
-        };
-
-        for depth in 0..CHUNK_SIZE {
-            for u in 0..CHUNK_SIZE {
-                for v in 0..CHUNK_SIZE {
-                    let pos = Self::get_position(u_axis, v_axis, depth_axis, depth, u, v);
-                    let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
-                    if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
-                        continue;
-                    }
-                    let index = x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
-                    if visited.get(index) {
-                        continue;
-                    }
-                    let block = chunk.get(x, y, z);
-                    if block.is_transpose() {
-                        continue;
-                    }
-                    if (exposed_cache[x][y][z] & (1 << direction)) == 0 {
-                        continue;
-                    }
-                    let (quad_width, quad_height) = Self::find_quad(
-                        chunk,
-                        depth,
-                        u,
-                        v,
-                        block,
-                        direction,
-                        u_axis,
-                        v_axis,
-                        depth_axis,
-                        &visited,
-                        exposed_cache,
-                    );
-
-                    if quad_width > 0 && quad_height > 0 {
-                        Self::create_greedy_quad(
-                            normal,
-                            depth,
-                            u,
-                            v,
-                            quad_width,
-                            quad_height,
-                            &block,
-                            u_axis,
-                            v_axis,
-                            depth_axis,
-                            &mut vertices,
-                            &mut indices,
-                            &mut index_offset,
-                        );
-
-                        // Mark quad as visited
-                        for du in 0..quad_width {
-                            for dv in 0..quad_height {
-                                let quad_pos = Self::get_position(
-                                    u_axis,
-                                    v_axis,
-                                    depth_axis,
-                                    depth,
-                                    u + du,
-                                    v + dv,
-                                );
-                                let (qx, qy, qz) = (
-                                    quad_pos.x as usize,
-                                    quad_pos.y as usize,
-                                    quad_pos.z as usize,
-                                );
-                                if qx < CHUNK_SIZE && qy < CHUNK_SIZE && qz < CHUNK_SIZE {
-                                    let quad_index =
-                                        qx * CHUNK_SIZE * CHUNK_SIZE + qy * CHUNK_SIZE + qz;
-                                    visited.set(quad_index);
-                                }
-                            }
-                        }
-                    }
+    /// Visible faces of one direction as `[depth][u]` rows of v bits.
+    fn face_masks(
+        columns: &[[ColumnMask; CHUNK_SIZE]; CHUNK_SIZE],
+        positive: bool,
+    ) -> [[RowMask; CHUNK_SIZE]; CHUNK_SIZE] {
+        let mut faces = [[0; CHUNK_SIZE]; CHUNK_SIZE];
+        for (u, row) in columns.iter().enumerate() {
+            for (v, &column) in row.iter().enumerate() {
+                // opaque cell whose neighbour along the normal is not
+                let visible = if positive {
+                    column & !(column >> 1)
+                } else {
+                    column & !(column << 1)
+                };
+                // drop the padding, only cells inside the chunk get faces
+                let mut visible = (visible >> 1) as RowMask;
+                while visible != 0 {
+                    let depth = visible.trailing_zeros() as usize;
+                    faces[depth][u] |= 1 << v;
+                    visible &= visible - 1;
                 }
             }
         }
-
-        (vertices, indices)
+        faces
     }
+}
 
-    fn find_quad(
-        chunk: &Chunk,
-        depth: usize,
-        start_u: usize,
-        start_v: usize,
-        target_block: Block,
-        direction: usize,
-        u_axis: Vector3<f32>,
-        v_axis: Vector3<f32>,
-        depth_axis: Vector3<f32>,
-        visited: &BitSet,
-        exposed_cache: &[[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
-    ) -> (usize, usize) {
-        let max_width = CHUNK_SIZE - start_u;
-        let max_height = CHUNK_SIZE - start_v;
-        let mut quad_width = 1;
-        let mut quad_height = 1;
-
-        // Expand horizontally with early break
-        for w in 1..max_width {
-            let mut valid = true;
-            for h in 0..quad_height {
-                let pos =
-                    Self::get_position(u_axis, v_axis, depth_axis, depth, start_u + w, start_v + h);
-                let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
-
-                if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
-                    valid = false;
-                    break;
-                }
-
-                let index = x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
-                if visited.get(index) {
-                    valid = false;
-                    break;
-                }
+// One depth layer of faces pointing the same way
+struct Slice<'a> {
+    snapshot: &'a ChunkSnapshot,
+    direction: usize,
+    normal: Vector3<f32>,
+    axes: (Vector3<f32>, Vector3<f32>, Vector3<f32>),
+    depth: usize,
+}
 
-                let block = chunk.get(x, y, z);
-                if block != target_block || block.is_transpose() {
-                    valid = false;
-                    break;
+impl Slice<'_> {
+    fn mesh(&self, mut rows: [RowMask; CHUNK_SIZE], vertices: &mut Vec<Vertex>) {
+        let keys = self.face_keys(&rows);
+        for u in 0..CHUNK_SIZE {
+            while rows[u] != 0 {
+                let v = rows[u].trailing_zeros() as usize;
+                let key = keys[u][v];
+                let bit = 1 << v;
+
+                let mut width = 1;
+                while u + width < CHUNK_SIZE
+                    && rows[u + width] & bit != 0
+                    && keys[u + width][v] == key
+                {
+                    width += 1;
                 }
+                let height = (u..u + width).fold(CHUNK_SIZE - v, |height, row| {
+                    Self::run_length(rows[row], &keys[row], v, key, height)
+                });
 
-                if (exposed_cache[x][y][z] & (1 << direction)) == 0 {
-                    valid = false;
-                    break;
+                let run = ((1u64 << height) - 1) as RowMask;
+                for row in &mut rows[u..u + width] {
+                    *row &= !(run << v);
                 }
-            }
 
-            if !valid {
-                break;
+                let quad = GreedyQuad {
+                    depth: self.depth,
+                    u,
+                    v,
+                    width,
+                    height,
+                    block: Block::from_id(key as u16),
+                    ao: (key >> 16) as u8,
+                };
+                create_greedy_quad(self.normal, &quad, self.axes, vertices);
             }
-            quad_width += 1;
         }
+    }
 
-        // Expand vertically with early break
-        for h in 1..max_height {
-            let mut valid = true;
-            for w in 0..quad_width {
-                let pos =
-                    Self::get_position(u_axis, v_axis, depth_axis, depth, start_u + w, start_v + h);
-                let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
-
-                if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
-                    valid = false;
-                    break;
-                }
-
-                let index = x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
-                if visited.get(index) {
-                    valid = false;
-                    break;
-                }
-
-                let block = chunk.get(x, y, z);
-                if block != target_block || block.is_transpose() {
-                    valid = false;
-                    break;
-                }
-
-                if (exposed_cache[x][y][z] & (1 << direction)) == 0 {
-                    valid = false;
-                    break;
-                }
-            }
-
-            if !valid {
-                break;
+    /// Block id and packed AO of every visible face, faces only merge with equal keys.
+    fn face_keys(&self, rows: &[RowMask; CHUNK_SIZE]) -> [[u32; CHUNK_SIZE]; CHUNK_SIZE] {
+        let (u_axis, v_axis, _) = self.axes;
+        let mut keys = [[0; CHUNK_SIZE]; CHUNK_SIZE];
+        for (u, &row) in rows.iter().enumerate() {
+            let mut bits = row;
+            while bits != 0 {
+                let v = bits.trailing_zeros() as usize;
+                let cell = slice_cell(self.direction / 2, u as i64, v as i64, self.depth as i64);
+                let (x, y, z) = (cell.0 as usize, cell.1 as usize, cell.2 as usize);
+                let ao = face_ao(self.snapshot, x, y, z, self.direction, u_axis, v_axis);
+                keys[u][v] = self.snapshot.get(x, y, z).id as u32 | (ao as u32) << 16;
+                bits &= bits - 1;
             }
-            quad_height += 1;
         }
-
-        (quad_width, quad_height)
+        keys
     }
 
-    fn create_greedy_quad(
-        normal: Vector3<f32>,
-        depth: usize,
-        u: usize,
+    /// Length of the run of faces with `key` starting at `v`, at most `limit`.
+    #[inline]
+    fn run_length(
+        row: RowMask,
+        keys: &[u32; CHUNK_SIZE],
         v: usize,
-        quad_width: usize,
-        quad_height: usize,
-        block: &Block,
-        u_axis: Vector3<f32>,
-        v_axis: Vector3<f32>,
-        depth_axis: Vector3<f32>,
-        vertices: &mut Vec<Vertex>,
-        indices: &mut Vec<u32>,
-        index_offset: &mut u32,
-    ) {
-        let base_pos = Self::get_position(u_axis, v_axis, depth_axis, depth, u, v);
-
-        // Adjust position to be the center of the quad
-        let center_offset_u = (quad_width as f32 - 1.0) * 0.5;
-        let center_offset_v = (quad_height as f32 - 1.0) * 0.5;
-
-        let center_pos = Vector3::new(
-            base_pos.x + u_axis.x * center_offset_u + v_axis.x * center_offset_v,
-            base_pos.y + u_axis.y * center_offset_u + v_axis.y * center_offset_v,
-            base_pos.z + u_axis.z * center_offset_u + v_axis.z * center_offset_v,
-        );
-
-        let (quad_vertices, quad_indices) = generate_face(
-            center_pos,
-            normal,
-            block.id as u32,
-            quad_width as f32,
-            quad_height as f32,
-        );
-        for vertex in quad_vertices {
-            vertices.push(vertex);
-        }
-        for mut index in quad_indices {
-            index += *index_offset;
-            indices.push(index);
-        }
-        *index_offset += 4;
+        key: u32,
+        limit: usize,
+    ) -> usize {
+        let set = ((!(row >> v)).trailing_zeros() as usize).min(limit);
+        (1..set).find(|&h| keys[v + h] != key).unwrap_or(set)
     }
+}
 
-    #[inline]
-    const fn get_position(
-        u_axis: Vector3<f32>,
-        v_axis: Vector3<f32>,
-        depth_axis: Vector3<f32>,
-        depth: usize,
-        u: usize,
-        v: usize,
-    ) -> Vector3<f32> {
-        Vector3::new(
-            u_axis.x * u as f32 + v_axis.x * v as f32 + depth_axis.x * depth as f32,
-            u_axis.y * u as f32 + v_axis.y * v as f32 + depth_axis.y * depth as f32,
-            u_axis.z * u as f32 + v_axis.z * v as f32 + depth_axis.z * depth as f32,
+/// AO of the four face corners packed two bits each, 3 is unoccluded.
+/// Corner bits are indexed by `positive_u | positive_v << 1`.
+pub(super) fn face_ao(
+    snapshot: &ChunkSnapshot,
+    x: usize,
+    y: usize,
+    z: usize,
+    direction: usize,
+    u_axis: Vector3<f32>,
+    v_axis: Vector3<f32>,
+) -> u8 {
+    let (dx, dy, dz) = DIRECTIONS[direction];
+    let front = (x as i64 + dx, y as i64 + dy, z as i64 + dz);
+    let u = (u_axis.x as i64, u_axis.y as i64, u_axis.z as i64);
+    let v = (v_axis.x as i64, v_axis.y as i64, v_axis.z as i64);
+    let offset = |pos: (i64, i64, i64), axis: (i64, i64, i64), sign: i64| {
+        (
+            pos.0 + axis.0 * sign,
+            pos.1 + axis.1 * sign,
+            pos.2 + axis.2 * sign,
         )
+    };
+
+    let mut packed = 0u8;
+    for corner in 0..4 {
+        let su = if corner & 1 != 0 { 1 } else { -1 };
+        let sv = if corner & 2 != 0 { 1 } else { -1 };
+        let side_u = snapshot.is_opaque(offset(front, u, su));
+        let side_v = snapshot.is_opaque(offset(front, v, sv));
+        let diagonal = snapshot.is_opaque(offset(offset(front, u, su), v, sv));
+        let ao = if side_u && side_v {
+            0
+        } else {
+            3 - side_u as u8 - side_v as u8 - diagonal as u8
+        };
+        packed |= ao << (corner * 2);
     }
+    packed
 }
 
-// Simple bit set for efficient visited tracking
-struct BitSet {
-    data: Vec<u64>,
-    size: usize,
+#[inline]
+fn corner_ao(packed: u8, positive_u: bool, positive_v: bool) -> u8 {
+    let corner = positive_u as u8 | (positive_v as u8) << 1;
+    (packed >> (corner * 2)) & 0b11
 }
 
-impl BitSet {
-    fn new(size: usize) -> Self {
-        let array_size = size.div_ceil(64);
-        Self {
-            data: vec![0; array_size],
-            size,
-        }
+/// In-slice axes `(u, v, depth)` of the faces pointing along `normal`.
+pub(super) fn slice_axes(normal: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>, Vector3<f32>) {
+    if normal.x.abs() > 0.5 {
+        (
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+        )
+    } else if normal.y.abs() > 0.5 {
+        (
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        )
+    } else {
+        (
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        )
     }
+}
 
-    fn get(&self, index: usize) -> bool {
-        if index >= self.size {
-            return false;
-        }
-        let word = index / 64;
-        let bit = index % 64;
-        (self.data[word] & (1 << bit)) != 0
+// chunk cell of slice coordinates, same mapping as `slice_axes` without the float math
+#[inline]
+fn slice_cell(axis: usize, u: i64, v: i64, depth: i64) -> (i64, i64, i64) {
+    match axis {
+        0 => (depth, u, v),
+        1 => (u, depth, v),
+        _ => (u, v, depth),
     }
+}
 
-    fn set(&mut self, index: usize) {
-        if index >= self.size {
-            return;
-        }
-        let word = index / 64;
-        let bit = index % 64;
-        self.data[word] |= 1 << bit;
+/// Faces of one block type merged into a rectangle of a slice, `(u, v)` is its lowest corner.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct GreedyQuad {
+    pub depth: usize,
+    pub u: usize,
+    pub v: usize,
+    pub width: usize,
+    pub height: usize,
+    pub block: Block,
+    /// packed per corner like `face_ao`
+    pub ao: u8,
+}
+
+pub(super) fn create_greedy_quad(
+    normal: Vector3<f32>,
+    quad: &GreedyQuad,
+    (u_axis, v_axis, depth_axis): (Vector3<f32>, Vector3<f32>, Vector3<f32>),
+    vertices: &mut Vec<Vertex>,
+) {
+    let base_pos = get_position(u_axis, v_axis, depth_axis, quad.depth, quad.u, quad.v);
+
+    // Adjust position to be the center of the quad
+    let center_offset_u = (quad.width as f32 - 1.0) * 0.5;
+    let center_offset_v = (quad.height as f32 - 1.0) * 0.5;
+
+    let center_pos = Vector3::new(
+        base_pos.x + u_axis.x * center_offset_u + v_axis.x * center_offset_v,
+        base_pos.y + u_axis.y * center_offset_u + v_axis.y * center_offset_v,
+        base_pos.z + u_axis.z * center_offset_u + v_axis.z * center_offset_v,
+    );
+
+    let mut quad_vertices = generate_face(
+        center_pos,
+        normal,
+        quad.block.id as u32,
+        quad.width as f32,
+        quad.height as f32,
+    );
+    for vertex in &mut quad_vertices {
+        let pos = vertex.local_pos();
+        let along_u = (pos[0] - center_pos.x) * u_axis.x
+            + (pos[1] - center_pos.y) * u_axis.y
+            + (pos[2] - center_pos.z) * u_axis.z;
+        let along_v = (pos[0] - center_pos.x) * v_axis.x
+            + (pos[1] - center_pos.y) * v_axis.y
+            + (pos[2] - center_pos.z) * v_axis.z;
+        vertex.set_ao(corner_ao(quad.ao, along_u > 0.0, along_v > 0.0) as u32);
     }
+    // split along the darker diagonal, the other one smears occlusion unevenly across the quad.
+    // Every quad shares `QUAD_INDICES`, so the split moves by starting the quad one corner later
+    if quad_vertices.len() == 4
+        && quad_vertices[0].ao() + quad_vertices[2].ao()
+            > quad_vertices[1].ao() + quad_vertices[3].ao()
+    {
+        quad_vertices.rotate_left(1);
+    }
+    vertices.extend(quad_vertices);
+}
+
+#[inline]
+pub(super) const fn get_position(
+    u_axis: Vector3<f32>,
+    v_axis: Vector3<f32>,
+    depth_axis: Vector3<f32>,
+    depth: usize,
+    u: usize,
+    v: usize,
+) -> Vector3<f32> {
+    Vector3::new(
+        u_axis.x * u as f32 + v_axis.x * v as f32 + depth_axis.x * depth as f32,
+        u_axis.y * u as f32 + v_axis.y * v as f32 + depth_axis.y * depth as f32,
+        u_axis.z * u as f32 + v_axis.z * v as f32 + depth_axis.z * depth as f32,
+    )
 }
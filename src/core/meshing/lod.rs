@@ -0,0 +1,54 @@
+use crate::core::{
+    block::Block,
+    chunk::{CHUNK_SIZE, Chunk},
+};
+
+/// Level of detail of a chunk mesh, each level halves the resolution along every axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lod(u8);
+
+impl Lod {
+    pub const FULL: Lod = Lod(0);
+    /// 8× downsampled, cells of 8³ blocks
+    pub const MAX: Lod = Lod(3);
+
+    /// Clamped to `MAX`.
+    pub fn new(level: u8) -> Self {
+        Self(level.min(Self::MAX.0))
+    }
+
+    pub fn level(self) -> u8 {
+        self.0
+    }
+
+    /// Edge length of one downsampled cell in blocks.
+    pub fn scale(self) -> usize {
+        1 << self.0
+    }
+
+    /// Representative block of the `scale`³ cell starting at `origin`: solid when at least
+    /// half of it is, taking the topmost solid block so surfaces keep their grass.
+    pub fn sample(self, chunk: &Chunk, origin: (usize, usize, usize)) -> Block {
+        let scale = self.scale();
+        debug_assert!(origin.0 + scale <= CHUNK_SIZE);
+        debug_assert!(origin.1 + scale <= CHUNK_SIZE);
+        debug_assert!(origin.2 + scale <= CHUNK_SIZE);
+        let mut solid = 0;
+        let mut top = None;
+        for z in (origin.2..origin.2 + scale).rev() {
+            for y in origin.1..origin.1 + scale {
+                for x in origin.0..origin.0 + scale {
+                    let block = chunk.get(x, y, z);
+                    if !block.is_transpose() {
+                        solid += 1;
+                        top.get_or_insert(block);
+                    }
+                }
+            }
+        }
+        match top {
+            Some(block) if 2 * solid >= scale * scale * scale => block,
+            _ => Block::air(),
+        }
+    }
+}
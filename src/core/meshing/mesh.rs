@@ -1,29 +1,75 @@
-use crate::core::meshing::Vertex;
+use std::ops::Range;
 
+use crate::core::meshing::{MAX_CHUNK_QUADS, QUAD_INDICES, Vertex};
+
+/// A chunk's mesh as the world keeps it. The vertices only stay until the renderer takes
+/// them for upload, the metadata lives on for culling and statistics.
 pub struct Mesh {
     pub vertices: Vec<Vertex>,
-    pub indices: Vec<u32>,
-    pub vertex_buffer: Option<wgpu::Buffer>,
-    pub index_buffer: Option<wgpu::Buffer>,
+    /// indices into the shared quad index buffer, six per quad
     pub index_count: u32,
+    /// index range of each face direction, in `Vertex::normal` order
+    pub direction_ranges: [Range<u32>; 6],
+    /// see `ChunkMesher::axis_aligned`, direction groups of other meshes are never culled
+    pub axis_aligned: bool,
+    /// bumped by every remesh
+    pub version: u32,
+    /// vertices that haven't been taken for upload yet
     pub is_dirty: bool,
 }
 
 impl Mesh {
-    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
+    pub fn new(vertices: Vec<Vertex>) -> Self {
         Self {
-            index_count: indices.len() as u32,
+            index_count: Self::index_count(&vertices),
+            direction_ranges: Self::direction_ranges(&vertices),
+            axis_aligned: true,
             vertices,
-            indices,
-            vertex_buffer: None,
-            index_buffer: None,
+            version: 0,
             is_dirty: true,
         }
     }
-    pub fn update(&mut self, vertices: Vec<Vertex>, indices: Vec<u32>) {
-        self.index_count = indices.len() as u32;
+
+    /// CPU-side bytes held by the vertex data.
+    pub fn memory_bytes(&self) -> usize {
+        self.vertices.capacity() * std::mem::size_of::<Vertex>()
+    }
+
+    pub fn update(&mut self, vertices: Vec<Vertex>) {
+        self.index_count = Self::index_count(&vertices);
+        self.direction_ranges = Self::direction_ranges(&vertices);
         self.vertices = vertices;
-        self.indices = indices;
+        self.version += 1;
         self.is_dirty = true;
     }
+
+    /// Hands the vertices over for upload and releases the CPU copy, the counts and ranges stay.
+    pub fn take_vertices(&mut self) -> Vec<Vertex> {
+        self.is_dirty = false;
+        std::mem::take(&mut self.vertices)
+    }
+
+    pub fn quad_count(&self) -> u32 {
+        self.index_count / QUAD_INDICES.len() as u32
+    }
+
+    fn index_count(vertices: &[Vertex]) -> u32 {
+        debug_assert!(vertices.len().is_multiple_of(4) && vertices.len() / 4 <= MAX_CHUNK_QUADS);
+        (vertices.len() / 4 * QUAD_INDICES.len()) as u32
+    }
+
+    /// Meshers emit their quads grouped by direction, so every direction is one index range.
+    fn direction_ranges(vertices: &[Vertex]) -> [Range<u32>; 6] {
+        debug_assert!(vertices.chunks(4).is_sorted_by_key(|quad| quad[0].normal()));
+        let mut counts = [0u32; 6];
+        for quad in vertices.chunks(4) {
+            counts[quad[0].normal() as usize] += QUAD_INDICES.len() as u32;
+        }
+        let mut start = 0;
+        counts.map(|count| {
+            let range = start..start + count;
+            start += count;
+            range
+        })
+    }
 }
@@ -1,9 +1,25 @@
+mod chunk_mesher;
 mod face_gen;
+mod far_tile;
 mod greedy_mesher;
+mod lod;
 mod mesh;
+mod naive_mesher;
+mod quad_indices;
+mod smooth_mesher;
+mod snapshot;
 mod vertex;
 
+pub use chunk_mesher::{ChunkMesher, MesherKind};
 pub use face_gen::generate_face;
+pub use far_tile::{FAR_TILE_CHUNKS, FarTile, FarVertex};
 pub use greedy_mesher::GreedyMesher;
+pub use lod::Lod;
 pub use mesh::Mesh;
-pub use vertex::Vertex;
+pub use naive_mesher::NaiveMesher;
+pub use quad_indices::{MAX_CHUNK_QUADS, QUAD_INDICES, quad_indices};
+pub use smooth_mesher::SmoothMesher;
+pub use snapshot::ChunkSnapshot;
+pub use vertex::{ChunkOrigin, Vertex};
+
+pub mod reference_mesher;
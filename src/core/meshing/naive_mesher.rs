@@ -0,0 +1,56 @@
+use crate::core::{
+    chunk::CHUNK_SIZE,
+    meshing::{
+        ChunkMesher, ChunkSnapshot, GreedyMesher, Vertex,
+        greedy_mesher::{DIRECTIONS, GreedyQuad, NORMALS, create_greedy_quad, face_ao, slice_axes},
+    },
+};
+
+/// One unit quad per visible block face, nothing merged. Slow and large, but simple
+/// enough to check the other meshers' coverage against.
+pub struct NaiveMesher;
+
+impl ChunkMesher for NaiveMesher {
+    fn mesh(&self, snapshot: &ChunkSnapshot) -> Vec<Vertex> {
+        if GreedyMesher::is_only_air_fast(snapshot) && GreedyMesher::is_only_air(snapshot) {
+            return Vec::new();
+        }
+        let mut vertices = Vec::new();
+
+        for (direction, &(dx, dy, dz)) in DIRECTIONS.iter().enumerate() {
+            let normal = NORMALS[direction];
+            let axes = slice_axes(normal);
+            let (u_axis, v_axis, _) = axes;
+            for x in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    for z in 0..CHUNK_SIZE {
+                        let (ix, iy, iz) = (x as i64, y as i64, z as i64);
+                        if !snapshot.is_opaque((ix, iy, iz))
+                            || snapshot.is_opaque((ix + dx, iy + dy, iz + dz))
+                        {
+                            continue;
+                        }
+                        // slice coordinates, the inverse of `slice_axes`
+                        let (depth, u, v) = match direction / 2 {
+                            0 => (x, y, z),
+                            1 => (y, x, z),
+                            _ => (z, x, y),
+                        };
+                        let quad = GreedyQuad {
+                            depth,
+                            u,
+                            v,
+                            width: 1,
+                            height: 1,
+                            block: snapshot.get(x, y, z),
+                            ao: face_ao(snapshot, x, y, z, direction, u_axis, v_axis),
+                        };
+                        create_greedy_quad(normal, &quad, axes, &mut vertices);
+                    }
+                }
+            }
+        }
+
+        vertices
+    }
+}
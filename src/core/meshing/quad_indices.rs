@@ -0,0 +1,13 @@
+use crate::core::chunk::CHUNK_VOLUME;
+
+/// Index pattern every quad is drawn with, offset by four vertices per quad.
+pub const QUAD_INDICES: [u32; 6] = [0, 1, 2, 2, 3, 0];
+/// Most quads one chunk mesh can hold, every face of a checkerboard of blocks.
+pub const MAX_CHUNK_QUADS: usize = CHUNK_VOLUME / 2 * 6;
+
+/// Indices of `quads` consecutive quads, what the renderer's shared index buffer holds.
+pub fn quad_indices(quads: usize) -> Vec<u32> {
+    (0..quads as u32)
+        .flat_map(|quad| QUAD_INDICES.map(|index| quad * 4 + index))
+        .collect()
+}
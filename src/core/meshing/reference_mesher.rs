@@ -0,0 +1,269 @@
+use crate::core::{
+    chunk::CHUNK_SIZE,
+    meshing::{
+        ChunkSnapshot, GreedyMesher, Vertex,
+        greedy_mesher::{
+            DIRECTIONS, GreedyQuad, NORMALS, create_greedy_quad, face_ao, get_position, slice_axes,
+        },
+    },
+};
+use cgmath::Vector3;
+
+/// Voxel by voxel greedy mesher, the straightforward version of `GreedyMesher`.
+/// Produces the same output and is kept as the reference the bitmask mesher is checked against.
+pub struct ReferenceMesher;
+
+impl ReferenceMesher {
+    pub fn build_mesh(chunk: &ChunkSnapshot) -> Vec<Vertex> {
+        if GreedyMesher::is_only_air_fast(chunk) && GreedyMesher::is_only_air(chunk) {
+            return Vec::new();
+        }
+        // Precompute exposed faces for the entire chunk
+        let exposed_cache = Self::build_exposed_cache(chunk);
+
+        NORMALS
+            .iter()
+            .enumerate()
+            .flat_map(|(dir, &normal)| {
+                Self::greedy_mesh_direction(chunk, normal, dir, &exposed_cache)
+            })
+            .collect()
+    }
+
+    fn build_exposed_cache(chunk: &ChunkSnapshot) -> ExposedCache {
+        let mut mask = [[[0u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE];
+        for (x, plane) in mask.iter_mut().enumerate() {
+            for (y, row) in plane.iter_mut().enumerate() {
+                for (z, exposed_mask) in row.iter_mut().enumerate() {
+                    for (dir, &(dx, dy, dz)) in DIRECTIONS.iter().enumerate() {
+                        if !chunk.is_opaque((x as i64 + dx, y as i64 + dy, z as i64 + dz)) {
+                            *exposed_mask |= 1 << dir;
+                        }
+                    }
+                }
+            }
+        }
+
+        ExposedCache { mask }
+    }
+
+    fn greedy_mesh_direction(
+        chunk: &ChunkSnapshot,
+        normal: Vector3<f32>,
+        direction: usize,
+        exposed_cache: &ExposedCache,
+    ) -> Vec<Vertex> {
+        let mut vertices = Vec::with_capacity(1024);
+
+        let mut visited = BitSet::new(CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE);
+
+        let axes = slice_axes(normal);
+        let (u_axis, v_axis, depth_axis) = axes;
+
+        for depth in 0..CHUNK_SIZE {
+            for u in 0..CHUNK_SIZE {
+                for v in 0..CHUNK_SIZE {
+                    let pos = get_position(u_axis, v_axis, depth_axis, depth, u, v);
+                    let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
+                    if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+                        continue;
+                    }
+                    let index = x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
+                    if visited.get(index) {
+                        continue;
+                    }
+                    let block = chunk.get(x, y, z);
+                    if block.is_transpose() {
+                        continue;
+                    }
+                    if !exposed_cache.is_exposed(x, y, z, direction) {
+                        continue;
+                    }
+                    let start = GreedyQuad {
+                        depth,
+                        u,
+                        v,
+                        width: 1,
+                        height: 1,
+                        block,
+                        ao: face_ao(chunk, x, y, z, direction, u_axis, v_axis),
+                    };
+                    let (quad_width, quad_height) =
+                        Self::find_quad(chunk, &start, direction, axes, &visited, exposed_cache);
+
+                    if quad_width > 0 && quad_height > 0 {
+                        let quad = GreedyQuad {
+                            width: quad_width,
+                            height: quad_height,
+                            ..start
+                        };
+                        create_greedy_quad(normal, &quad, axes, &mut vertices);
+
+                        // Mark quad as visited
+                        for du in 0..quad_width {
+                            for dv in 0..quad_height {
+                                let quad_pos =
+                                    get_position(u_axis, v_axis, depth_axis, depth, u + du, v + dv);
+                                let (qx, qy, qz) = (
+                                    quad_pos.x as usize,
+                                    quad_pos.y as usize,
+                                    quad_pos.z as usize,
+                                );
+                                if qx < CHUNK_SIZE && qy < CHUNK_SIZE && qz < CHUNK_SIZE {
+                                    let quad_index =
+                                        qx * CHUNK_SIZE * CHUNK_SIZE + qy * CHUNK_SIZE + qz;
+                                    visited.set(quad_index);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        vertices
+    }
+
+    /// Size of the largest quad growing from `start`'s corner with its block and AO.
+    fn find_quad(
+        chunk: &ChunkSnapshot,
+        start: &GreedyQuad,
+        direction: usize,
+        (u_axis, v_axis, depth_axis): (Vector3<f32>, Vector3<f32>, Vector3<f32>),
+        visited: &BitSet,
+        exposed_cache: &ExposedCache,
+    ) -> (usize, usize) {
+        let (depth, start_u, start_v) = (start.depth, start.u, start.v);
+        let (target_block, target_ao) = (start.block, start.ao);
+        let max_width = CHUNK_SIZE - start_u;
+        let max_height = CHUNK_SIZE - start_v;
+        let mut quad_width = 1;
+        let mut quad_height = 1;
+
+        // Expand horizontally with early break
+        for w in 1..max_width {
+            let mut valid = true;
+            for h in 0..quad_height {
+                let pos = get_position(u_axis, v_axis, depth_axis, depth, start_u + w, start_v + h);
+                let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
+
+                if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+                    valid = false;
+                    break;
+                }
+
+                let index = x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
+                if visited.get(index) {
+                    valid = false;
+                    break;
+                }
+
+                let block = chunk.get(x, y, z);
+                if block != target_block || block.is_transpose() {
+                    valid = false;
+                    break;
+                }
+
+                // faces only merge when every corner is shaded the same
+                if !exposed_cache.is_exposed(x, y, z, direction)
+                    || face_ao(chunk, x, y, z, direction, u_axis, v_axis) != target_ao
+                {
+                    valid = false;
+                    break;
+                }
+            }
+
+            if !valid {
+                break;
+            }
+            quad_width += 1;
+        }
+
+        // Expand vertically with early break
+        for h in 1..max_height {
+            let mut valid = true;
+            for w in 0..quad_width {
+                let pos = get_position(u_axis, v_axis, depth_axis, depth, start_u + w, start_v + h);
+                let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
+
+                if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+                    valid = false;
+                    break;
+                }
+
+                let index = x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
+                if visited.get(index) {
+                    valid = false;
+                    break;
+                }
+
+                let block = chunk.get(x, y, z);
+                if block != target_block || block.is_transpose() {
+                    valid = false;
+                    break;
+                }
+
+                // faces only merge when every corner is shaded the same
+                if !exposed_cache.is_exposed(x, y, z, direction)
+                    || face_ao(chunk, x, y, z, direction, u_axis, v_axis) != target_ao
+                {
+                    valid = false;
+                    break;
+                }
+            }
+
+            if !valid {
+                break;
+            }
+            quad_height += 1;
+        }
+
+        (quad_width, quad_height)
+    }
+}
+
+// Exposed faces per block, bit `dir` set when the face towards `DIRECTIONS[dir]` is visible
+struct ExposedCache {
+    mask: [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+}
+
+impl ExposedCache {
+    #[inline]
+    fn is_exposed(&self, x: usize, y: usize, z: usize, direction: usize) -> bool {
+        (self.mask[x][y][z] & (1 << direction)) != 0
+    }
+}
+
+// Simple bit set for efficient visited tracking
+struct BitSet {
+    data: Vec<u64>,
+    size: usize,
+}
+
+impl BitSet {
+    fn new(size: usize) -> Self {
+        let array_size = size.div_ceil(64);
+        Self {
+            data: vec![0; array_size],
+            size,
+        }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        if index >= self.size {
+            return false;
+        }
+        let word = index / 64;
+        let bit = index % 64;
+        (self.data[word] & (1 << bit)) != 0
+    }
+
+    fn set(&mut self, index: usize) {
+        if index >= self.size {
+            return;
+        }
+        let word = index / 64;
+        let bit = index % 64;
+        self.data[word] |= 1 << bit;
+    }
+}
@@ -0,0 +1,147 @@
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::core::{
+    chunk::CHUNK_SIZE,
+    meshing::{
+        ChunkMesher, ChunkSnapshot, GreedyMesher, Vertex,
+        greedy_mesher::{DIRECTIONS, NORMALS},
+    },
+};
+
+// dual cells per axis, their minimum corners run from -1 to CHUNK_SIZE - 1
+const CELLS: usize = CHUNK_SIZE + 1;
+// the dual cells around a face edge in quad order, as offsets along the face's (u, v)
+const QUAD_CELLS: [(i64, i64); 4] = [(-1, -1), (0, -1), (0, 0), (-1, 0)];
+
+/// Surface nets over block opacity. Every visible block face becomes a quad between the
+/// vertices of the four dual cells around it, each vertex sits at the mean of its cell's
+/// surface crossings, so steps and slopes come out smoothed. Cells on the border are
+/// computed the same way by the neighbouring chunk, seams stay closed.
+pub struct SmoothMesher;
+
+#[derive(Clone, Copy)]
+struct CellVertex {
+    position: Vector3<f32>,
+    /// opacity gradient pointing out of the solid side, zero where it cancels out
+    normal: Vector3<f32>,
+}
+
+impl ChunkMesher for SmoothMesher {
+    fn mesh(&self, snapshot: &ChunkSnapshot) -> Vec<Vertex> {
+        if GreedyMesher::is_only_air_fast(snapshot) && GreedyMesher::is_only_air(snapshot) {
+            return Vec::new();
+        }
+        let mut cells = vec![None; CELLS * CELLS * CELLS];
+        let mut vertices = Vec::new();
+
+        for (direction, &(dx, dy, dz)) in DIRECTIONS.iter().enumerate() {
+            let axis = direction / 2;
+            let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+            for x in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    for z in 0..CHUNK_SIZE {
+                        let (ix, iy, iz) = (x as i64, y as i64, z as i64);
+                        if !snapshot.is_opaque((ix, iy, iz))
+                            || snapshot.is_opaque((ix + dx, iy + dy, iz + dz))
+                        {
+                            continue;
+                        }
+                        // cells share the face's edge, so their minimum corner along the
+                        // normal is the lower of the two blocks
+                        let mut edge = [ix, iy, iz];
+                        edge[axis] = edge[axis].min(edge[axis] + [dx, dy, dz][axis]);
+                        let mut quad = QUAD_CELLS.map(|(du, dv)| {
+                            let mut cell = edge;
+                            cell[u_axis] += du;
+                            cell[v_axis] += dv;
+                            Self::cell_vertex(snapshot, &mut cells, cell)
+                        });
+                        // (u, v, normal) is right-handed, faces looking down the axis turn around
+                        if direction % 2 == 1 {
+                            quad.reverse();
+                        }
+
+                        let tex_id = snapshot.get(x, y, z).id as u32;
+                        for cell in quad {
+                            let normal = if cell.normal.is_zero() {
+                                NORMALS[direction]
+                            } else {
+                                cell.normal.normalize()
+                            };
+                            let position: [f32; 3] = cell.position.into();
+                            let uv = [position[u_axis], position[v_axis]]
+                                .map(|c| (c + 0.5).round().clamp(0.0, 63.0) as u32);
+                            vertices.push(Vertex::at(
+                                position,
+                                uv,
+                                direction as u32,
+                                tex_id,
+                                Self::shade(normal),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        vertices
+    }
+
+    // slopes and cut corners tilt away from their direction label
+    fn axis_aligned(&self) -> bool {
+        false
+    }
+}
+
+impl SmoothMesher {
+    /// Vertex of the dual cell whose corners are the block centers from `min` to `min + 1`.
+    fn cell_vertex(
+        snapshot: &ChunkSnapshot,
+        cells: &mut [Option<CellVertex>],
+        min: [i64; 3],
+    ) -> CellVertex {
+        let index = (min[0] + 1) as usize
+            + (min[1] + 1) as usize * CELLS
+            + (min[2] + 1) as usize * CELLS * CELLS;
+        if let Some(vertex) = cells[index] {
+            return vertex;
+        }
+
+        let offset = |corner: usize| [corner & 1, corner >> 1 & 1, corner >> 2 & 1];
+        let solid: [bool; 8] = std::array::from_fn(|corner| {
+            let [ox, oy, oz] = offset(corner).map(|o| o as i64);
+            snapshot.is_opaque((min[0] + ox, min[1] + oy, min[2] + oz))
+        });
+
+        let mut crossings = Vector3::zero();
+        let mut count = 0;
+        let mut gradient = Vector3::zero();
+        for corner in 0..8 {
+            let [ox, oy, oz] = offset(corner).map(|o| o as f32);
+            if solid[corner] {
+                gradient += Vector3::new(ox, oy, oz) * 2.0 - Vector3::new(1.0, 1.0, 1.0);
+            }
+            for bit in [1, 2, 4] {
+                if corner & bit == 0 && solid[corner] != solid[corner | bit] {
+                    let [ex, ey, ez] = offset(bit).map(|o| o as f32 * 0.5);
+                    crossings += Vector3::new(ox + ex, oy + ey, oz + ez);
+                    count += 1;
+                }
+            }
+        }
+        // only cells next to a visible face are asked for, they always have a crossing
+        debug_assert!(count > 0);
+        let vertex = CellVertex {
+            position: Vector3::new(min[0] as f32, min[1] as f32, min[2] as f32)
+                + crossings / count.max(1) as f32,
+            normal: -gradient,
+        };
+        cells[index] = Some(vertex);
+        vertex
+    }
+
+    /// There is no lighting in the shader, the AO level doubles as a sky-facing shade.
+    fn shade(normal: Vector3<f32>) -> u32 {
+        ((1.5 + 1.5 * normal.z).round() as u32).min(Vertex::UNOCCLUDED)
+    }
+}
@@ -0,0 +1,126 @@
+use cgmath::Vector3;
+
+use crate::{
+    core::{
+        block::{Block, BlockType},
+        chunk::{CHUNK_SIZE, Chunk},
+        meshing::Lod,
+    },
+    world::{World, surface_map::ChunkFill},
+};
+
+const PADDED: usize = CHUNK_SIZE + 2;
+const PADDED_VOLUME: usize = PADDED * PADDED * PADDED;
+
+/// A chunk plus a one block border copied from its neighbours, everything the mesher reads.
+/// Faces need the six face neighbours, AO samples also reach into the edge and corner ones.
+/// Owns its data, so meshing jobs don't borrow the world and can move to other threads.
+#[derive(Clone)]
+pub struct ChunkSnapshot {
+    pub pos: Vector3<i64>,
+    // x fastest like `Chunk::index`, coordinates -1..=CHUNK_SIZE shifted by one
+    blocks: Vec<Block>,
+}
+
+impl ChunkSnapshot {
+    /// Border cells of unloaded neighbours are air where the surface map knows they are empty
+    /// and solid otherwise, so faces towards unknown terrain stay hidden.
+    ///
+    /// Chunks with a coarser `lod` are captured downsampled, every cell of `scale`³ blocks
+    /// holding its representative block. Borders towards a face neighbour of another level
+    /// are left empty, both sides then close the seam with a wall of border faces.
+    pub fn capture(chunk: &Chunk, world: &World) -> Self {
+        let lod = chunk.lod;
+        let mut blocks = vec![Block::air(); PADDED_VOLUME];
+        if lod == Lod::FULL {
+            for z in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    let src = Chunk::index(0, y, z);
+                    let dst = Self::index(0, y as i64, z as i64);
+                    blocks[dst..dst + CHUNK_SIZE]
+                        .copy_from_slice(&chunk.blocks[src..src + CHUNK_SIZE]);
+                }
+            }
+        }
+
+        let (size, scale) = (CHUNK_SIZE as i64, lod.scale());
+        // per axis offset: padded coordinates of each cell and the local origin it samples from
+        let cells = |offset: i64| -> Vec<(std::ops::RangeInclusive<i64>, usize)> {
+            match offset {
+                -1 => vec![(-1..=-1, CHUNK_SIZE - scale)],
+                0 => (0..CHUNK_SIZE)
+                    .step_by(scale)
+                    .map(|c| (c as i64..=(c + scale) as i64 - 1, c))
+                    .collect(),
+                _ => vec![(size..=size, 0)],
+            }
+        };
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let key = (chunk._pos.x + dx, chunk._pos.y + dy, chunk._pos.z + dz);
+                    let neighbor = match (dx, dy, dz) {
+                        (0, 0, 0) if lod == Lod::FULL => continue,
+                        (0, 0, 0) => Some(chunk),
+                        _ => world.chunks.get(&key),
+                    };
+                    let is_face = dx.abs() + dy.abs() + dz.abs() == 1;
+                    if is_face && neighbor.is_some_and(|neighbor| neighbor.lod != lod) {
+                        continue;
+                    }
+                    let fallback = match world.known_fill(&key) {
+                        Some(ChunkFill::Empty) => Block::air(),
+                        _ => Block::new(BlockType::Stone),
+                    };
+                    for (zs, z0) in cells(dz) {
+                        for (ys, y0) in cells(dy) {
+                            for (xs, x0) in cells(dx) {
+                                let block = neighbor.map_or(fallback, |neighbor| {
+                                    lod.sample(neighbor, (x0, y0, z0))
+                                });
+                                for z in zs.clone() {
+                                    for y in ys.clone() {
+                                        for x in xs.clone() {
+                                            blocks[Self::index(x, y, z)] = block;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            pos: chunk._pos,
+            blocks,
+        }
+    }
+
+    #[inline(always)]
+    fn index(x: i64, y: i64, z: i64) -> usize {
+        (x + 1) as usize + (y + 1) as usize * PADDED + (z + 1) as usize * PADDED * PADDED
+    }
+
+    /// Block inside the chunk, same as `Chunk::get`.
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Block {
+        debug_assert!(x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE);
+        self.blocks[Self::index(x as i64, y as i64, z as i64)]
+    }
+
+    /// Any cell of the chunk or its border, coordinates in -1..=CHUNK_SIZE.
+    #[inline(always)]
+    pub fn get_padded(&self, x: i64, y: i64, z: i64) -> Block {
+        debug_assert!((-1..=CHUNK_SIZE as i64).contains(&x));
+        debug_assert!((-1..=CHUNK_SIZE as i64).contains(&y));
+        debug_assert!((-1..=CHUNK_SIZE as i64).contains(&z));
+        self.blocks[Self::index(x, y, z)]
+    }
+
+    #[inline(always)]
+    pub fn is_opaque(&self, pos: (i64, i64, i64)) -> bool {
+        !self.get_padded(pos.0, pos.1, pos.2).is_transpose()
+    }
+}
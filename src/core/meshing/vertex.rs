@@ -1,47 +1,148 @@
 use wgpu::VertexFormat;
 
+use crate::core::chunk::CHUNK_SIZE;
+
+// bit layout of the packed words
+const POS_BITS: u32 = 6;
+const POS_MASK: u32 = (1 << POS_BITS) - 1;
+const NORMAL_SHIFT: u32 = 18;
+const AO_SHIFT: u32 = 21;
+const UV_BITS: u32 = 6;
+const UV_MASK: u32 = (1 << UV_BITS) - 1;
+const TEX_SHIFT: u32 = 12;
+const TEX_MASK: u32 = 0xFFFF;
+const FRACTION_SHIFT: u32 = 23;
+const FRACTION_BITS: u32 = 3;
+const FRACTION_MASK: u32 = (1 << FRACTION_BITS) - 1;
+// stored positions are offset by this many blocks, so smooth vertices a block outside the chunk fit
+const POS_BIAS: f32 = 1.5;
+
+/// Chunk-local vertex packed into 8 bytes, placed in the world by the draw's `ChunkOrigin`.
+///
+/// - word 0: x, y, z offset by one and a half blocks (6 bits each), normal index (3), AO (2),
+///   then eighths of a block for x, y, z (3 bits each), zero for block meshes
+/// - word 1: u, v in blocks of the quad size (6 bits each), texture layer (16)
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
-    pub pos: [f32; 3],
-    pub tex_coord: [f32; 2],
-    pub tex_id: u32,
+    pub data: [u32; 2],
 }
 
 impl Vertex {
-    pub const fn new(pos: [f32; 3], tex_coord: [f32; 2], tex_id: u32) -> Self {
+    /// ambient occlusion level of a corner nothing occludes, 0 is fully occluded
+    pub const UNOCCLUDED: u32 = 3;
+
+    /// `corner` is the block corner shifted by half a block, so 0..=32 in a chunk.
+    pub const fn new(corner: [u32; 3], uv: [u32; 2], normal: u32, tex_id: u32, ao: u32) -> Self {
         Self {
-            pos,
-            tex_coord,
-            tex_id,
+            data: [
+                ((corner[0] + 1) & POS_MASK)
+                    | ((corner[1] + 1) & POS_MASK) << POS_BITS
+                    | ((corner[2] + 1) & POS_MASK) << (2 * POS_BITS)
+                    | (normal & 0b111) << NORMAL_SHIFT
+                    | (ao & 0b11) << AO_SHIFT,
+                (uv[0] & UV_MASK) | (uv[1] & UV_MASK) << UV_BITS | (tex_id & TEX_MASK) << TEX_SHIFT,
+            ],
         }
     }
 
+    /// Vertex off the block grid, for smooth meshes. `position` is chunk-local and rounded to
+    /// an eighth of a block, anything from -1.5 to just under 62.5 fits.
+    pub fn at(position: [f32; 3], uv: [u32; 2], normal: u32, tex_id: u32, ao: u32) -> Self {
+        let max = ((POS_MASK + 1) << FRACTION_BITS) - 1;
+        let fixed = position
+            .map(|p| (((p + POS_BIAS) * (1 << FRACTION_BITS) as f32).round() as u32).min(max));
+        let mut vertex = Self::new([0; 3], uv, normal, tex_id, ao);
+        vertex.data[0] &= !(POS_MASK | POS_MASK << POS_BITS | POS_MASK << (2 * POS_BITS));
+        for (axis, fixed) in fixed.into_iter().enumerate() {
+            let axis = axis as u32;
+            vertex.data[0] |= (fixed >> FRACTION_BITS) << (axis * POS_BITS)
+                | (fixed & FRACTION_MASK) << (FRACTION_SHIFT + axis * FRACTION_BITS);
+        }
+        vertex
+    }
+
+    /// The block corner given to `new`, shifted by half a block. Only meaningful for block meshes.
+    pub const fn corner(&self) -> [u32; 3] {
+        [
+            (self.data[0] & POS_MASK).wrapping_sub(1),
+            ((self.data[0] >> POS_BITS) & POS_MASK).wrapping_sub(1),
+            ((self.data[0] >> (2 * POS_BITS)) & POS_MASK).wrapping_sub(1),
+        ]
+    }
+
+    /// Position relative to the chunk origin, blocks are centered on integer coordinates.
+    pub fn local_pos(&self) -> [f32; 3] {
+        std::array::from_fn(|axis| {
+            let axis = axis as u32;
+            let whole = (self.data[0] >> (axis * POS_BITS)) & POS_MASK;
+            let fraction =
+                (self.data[0] >> (FRACTION_SHIFT + axis * FRACTION_BITS)) & FRACTION_MASK;
+            whole as f32 + fraction as f32 / (1 << FRACTION_BITS) as f32 - POS_BIAS
+        })
+    }
+
+    pub const fn uv(&self) -> [u32; 2] {
+        [self.data[1] & UV_MASK, (self.data[1] >> UV_BITS) & UV_MASK]
+    }
+
+    pub const fn normal(&self) -> u32 {
+        (self.data[0] >> NORMAL_SHIFT) & 0b111
+    }
+
+    pub const fn tex_id(&self) -> u32 {
+        (self.data[1] >> TEX_SHIFT) & TEX_MASK
+    }
+
+    pub const fn ao(&self) -> u32 {
+        (self.data[0] >> AO_SHIFT) & 0b11
+    }
+
+    pub fn set_ao(&mut self, ao: u32) {
+        self.data[0] = (self.data[0] & !(0b11 << AO_SHIFT)) | (ao & 0b11) << AO_SHIFT;
+    }
+
     pub const fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                wgpu::VertexAttribute {
-                    // position
-                    offset: 0,
-                    shader_location: 0,
-                    format: VertexFormat::Float32x3,
-                },
-                wgpu::VertexAttribute {
-                    // texture_coords
-                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
-                    shader_location: 1,
-                    format: VertexFormat::Float32x2,
-                },
-                wgpu::VertexAttribute {
-                    // texture_id
-                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress
-                        + std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
-                    shader_location: 2,
-                    format: VertexFormat::Uint32,
-                },
-            ],
+            attributes: &[wgpu::VertexAttribute {
+                // packed words, unpacked in the shader
+                offset: 0,
+                shader_location: 0,
+                format: VertexFormat::Uint32x2,
+            }],
+        }
+    }
+}
+
+/// World block position of a chunk's minimum corner, one instance per chunk draw.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ChunkOrigin {
+    pub origin: [i32; 3],
+}
+
+impl ChunkOrigin {
+    pub const fn new(origin: [i32; 3]) -> Self {
+        Self { origin }
+    }
+
+    /// Origin of the chunk at `key`, `None` if its blocks lie beyond what `i32` can address.
+    pub fn of_chunk(key: (i64, i64, i64)) -> Option<Self> {
+        let block = |n: i64| i32::try_from(n.checked_mul(CHUNK_SIZE as i64)?).ok();
+        Some(Self::new([block(key.0)?, block(key.1)?, block(key.2)?]))
+    }
+
+    pub const fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 1,
+                format: VertexFormat::Sint32x3,
+            }],
         }
     }
 }
@@ -1,4 +1,5 @@
 pub mod block;
 pub mod chunk;
+pub mod chunk_state;
 pub mod meshing;
 pub mod render;
@@ -0,0 +1,165 @@
+use image::GenericImageView;
+use wgpu::*;
+
+use crate::core::render::{texture_array::TextureArray, texture_atlas::TextureAtlas};
+
+/// How block textures reach the chunk shader.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextureMode {
+    /// The array, unless the textures differ in size or exceed the adapter's array layers.
+    #[default]
+    Auto,
+    /// One `D2Array` layer per texture, all of the same size.
+    Array,
+    /// One packed 2D texture, tiled per block in the shader.
+    Atlas,
+}
+
+impl TextureMode {
+    /// Picks `Array` or `Atlas` for `Auto`, other modes are kept.
+    /// Fails if `Auto` has to decode a texture that isn't a valid image.
+    pub fn resolve(self, textures_data: &[&[u8]], max_array_layers: u32) -> anyhow::Result<Self> {
+        if self != TextureMode::Auto {
+            return Ok(self);
+        }
+        let sizes = textures_data
+            .iter()
+            .map(|bytes| Ok(image::load_from_memory(bytes)?.dimensions()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let same_size = sizes.windows(2).all(|pair| pair[0] == pair[1]);
+        if same_size && textures_data.len() as u32 <= max_array_layers {
+            Ok(TextureMode::Array)
+        } else {
+            Ok(TextureMode::Atlas)
+        }
+    }
+}
+
+/// Block textures in the resolved mode, bound at group 1 of the chunk pipeline.
+/// Both modes share the sampler binding, each has its own texture bindings and
+/// fragment entry point in `shader.wgsl`.
+pub enum BlockTextures {
+    Array(TextureArray),
+    Atlas(TextureAtlas),
+}
+
+impl BlockTextures {
+    /// `mode` must be resolved already.
+    pub fn new(
+        device: &Device,
+        queue: &Queue,
+        textures_data: &[&[u8]],
+        mode: TextureMode,
+    ) -> anyhow::Result<(Self, BindGroupLayout, BindGroup)> {
+        let textures = match mode {
+            TextureMode::Atlas => Self::Atlas(TextureAtlas::new(
+                device,
+                queue,
+                textures_data,
+                Some("block_atlas"),
+            )?),
+            _ => Self::Array(TextureArray::new(
+                device,
+                queue,
+                textures_data,
+                Some("block_textures"),
+            )?),
+        };
+        let sampler_entry = BindGroupLayoutEntry {
+            binding: 1,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+            count: None,
+        };
+        let texture_entry = |binding, view_dimension| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let (layout, bind_group) = match &textures {
+            Self::Array(array) => {
+                let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    entries: &[
+                        texture_entry(0, TextureViewDimension::D2Array),
+                        sampler_entry,
+                    ],
+                    label: Some("texture_bind_group_layout"),
+                });
+                let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                    layout: &layout,
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 0,
+                            resource: BindingResource::TextureView(&array.view),
+                        },
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: BindingResource::Sampler(&array.sampler),
+                        },
+                    ],
+                    label: Some("texture_bind_group"),
+                });
+                (layout, bind_group)
+            }
+            Self::Atlas(atlas) => {
+                let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    entries: &[
+                        sampler_entry,
+                        texture_entry(2, TextureViewDimension::D2),
+                        BindGroupLayoutEntry {
+                            binding: 3,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ],
+                    label: Some("atlas_bind_group_layout"),
+                });
+                let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                    layout: &layout,
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: BindingResource::Sampler(&atlas.sampler),
+                        },
+                        BindGroupEntry {
+                            binding: 2,
+                            resource: BindingResource::TextureView(&atlas.view),
+                        },
+                        BindGroupEntry {
+                            binding: 3,
+                            resource: atlas.rect_buffer.as_entire_binding(),
+                        },
+                    ],
+                    label: Some("atlas_bind_group"),
+                });
+                (layout, bind_group)
+            }
+        };
+        Ok((textures, layout, bind_group))
+    }
+
+    pub fn mode(&self) -> TextureMode {
+        match self {
+            Self::Array(_) => TextureMode::Array,
+            Self::Atlas(_) => TextureMode::Atlas,
+        }
+    }
+
+    pub fn fragment_entry_point(&self) -> &'static str {
+        match self {
+            Self::Array(_) => "fs_main",
+            Self::Atlas(_) => "fs_atlas",
+        }
+    }
+}
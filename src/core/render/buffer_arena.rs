@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use crate::core::meshing::Vertex;
+
+const VERTEX_SIZE: u64 = std::mem::size_of::<Vertex>() as u64;
+/// Vertices the chunk arena starts out with, 8 MiB.
+pub const INITIAL_ARENA_VERTICES: u64 = 1 << 20;
+
+/// Block of arena units handed out by `ArenaAllocator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaAllocation {
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// First-fit free-list suballocator over `capacity` units, neighbouring free blocks merge
+/// again on free. Knows nothing about the GPU, `ChunkArena` puts a buffer behind it.
+#[derive(Debug, Clone)]
+pub struct ArenaAllocator {
+    capacity: u64,
+    // free blocks, size by offset
+    free: BTreeMap<u64, u64>,
+}
+
+impl ArenaAllocator {
+    pub fn new(capacity: u64) -> Self {
+        let mut free = BTreeMap::new();
+        if capacity > 0 {
+            free.insert(0, capacity);
+        }
+        Self { capacity, free }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    pub fn free_units(&self) -> u64 {
+        self.free.values().sum()
+    }
+
+    pub fn used_units(&self) -> u64 {
+        self.capacity - self.free_units()
+    }
+
+    pub fn largest_free_block(&self) -> u64 {
+        self.free.values().copied().max().unwrap_or(0)
+    }
+
+    /// Free blocks in offset order, more than one means the arena is fragmented.
+    pub fn free_blocks(&self) -> impl Iterator<Item = ArenaAllocation> + '_ {
+        self.free
+            .iter()
+            .map(|(&offset, &size)| ArenaAllocation { offset, size })
+    }
+
+    /// Lowest free block that fits, `None` when none does and the arena has to `grow`.
+    pub fn allocate(&mut self, size: u64) -> Option<ArenaAllocation> {
+        debug_assert!(size > 0, "empty allocation");
+        let (&offset, &free) = self.free.iter().find(|&(_, &free)| free >= size)?;
+        self.free.remove(&offset);
+        if free > size {
+            self.free.insert(offset + size, free - size);
+        }
+        Some(ArenaAllocation { offset, size })
+    }
+
+    pub fn free(&mut self, allocation: ArenaAllocation) {
+        let ArenaAllocation {
+            mut offset,
+            mut size,
+        } = allocation;
+        let end = offset + size;
+        debug_assert!(end <= self.capacity, "allocation outside the arena");
+        if let Some((&previous, &previous_size)) = self.free.range(..offset).next_back() {
+            debug_assert!(previous + previous_size <= offset, "double free");
+            if previous + previous_size == offset {
+                self.free.remove(&previous);
+                offset = previous;
+                size += previous_size;
+            }
+        }
+        if let Some((&next, &next_size)) = self.free.range(allocation.offset..).next() {
+            debug_assert!(next >= end, "double free");
+            if next == end {
+                self.free.remove(&next);
+                size += next_size;
+            }
+        }
+        self.free.insert(offset, size);
+    }
+
+    /// Gives the tail of `allocation` past `size` back, the front stays where it is.
+    pub fn shrink(&mut self, allocation: ArenaAllocation, size: u64) -> ArenaAllocation {
+        debug_assert!(size > 0 && size <= allocation.size);
+        if size < allocation.size {
+            self.free(ArenaAllocation {
+                offset: allocation.offset + size,
+                size: allocation.size - size,
+            });
+        }
+        ArenaAllocation {
+            offset: allocation.offset,
+            size,
+        }
+    }
+
+    /// Extends the arena to `capacity` units, the new space joins the free block at the end.
+    pub fn grow(&mut self, capacity: u64) {
+        debug_assert!(capacity >= self.capacity, "arenas never shrink");
+        if capacity > self.capacity {
+            let old = self.capacity;
+            self.capacity = capacity;
+            self.free(ArenaAllocation {
+                offset: old,
+                size: capacity - old,
+            });
+        }
+    }
+}
+
+/// Vertices of every chunk in one vertex buffer, suballocated in whole vertices so an
+/// allocation's offset is the draw's base vertex. Grows by doubling and copying the old
+/// contents over when an upload doesn't fit.
+pub struct ChunkArena {
+    pub buffer: wgpu::Buffer,
+    allocator: ArenaAllocator,
+}
+
+impl ChunkArena {
+    pub fn new(device: &wgpu::Device, vertices: u64) -> Self {
+        Self {
+            buffer: Self::create_buffer(device, vertices),
+            allocator: ArenaAllocator::new(vertices),
+        }
+    }
+
+    fn create_buffer(device: &wgpu::Device, vertices: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Chunk Arena"),
+            size: vertices * VERTEX_SIZE,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        })
+    }
+
+    pub fn allocator(&self) -> &ArenaAllocator {
+        &self.allocator
+    }
+
+    /// Bytes of allocated vertices, free space left by growing or evicting isn't counted.
+    pub fn used_bytes(&self) -> u64 {
+        self.allocator.used_units() * VERTEX_SIZE
+    }
+
+    /// `None` when the arena would outgrow the device's buffer size limit.
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertices: &[Vertex],
+    ) -> Option<ArenaAllocation> {
+        let size = vertices.len() as u64;
+        let allocation = match self.allocator.allocate(size) {
+            Some(allocation) => allocation,
+            None => {
+                self.grow(device, queue, size)?;
+                self.allocator.allocate(size)?
+            }
+        };
+        queue.write_buffer(
+            &self.buffer,
+            allocation.offset * VERTEX_SIZE,
+            bytemuck::cast_slice(vertices),
+        );
+        Some(allocation)
+    }
+
+    /// Overwrites `allocation` in place when `vertices` fit, a mesh shrunk below half its
+    /// allocation hands the rest back. `None` when it doesn't fit and needs a new `upload`.
+    pub fn rewrite(
+        &mut self,
+        queue: &wgpu::Queue,
+        allocation: ArenaAllocation,
+        vertices: &[Vertex],
+    ) -> Option<ArenaAllocation> {
+        let size = vertices.len() as u64;
+        if size == 0 || size > allocation.size {
+            return None;
+        }
+        let allocation = if size * 2 < allocation.size {
+            self.allocator.shrink(allocation, size)
+        } else {
+            allocation
+        };
+        queue.write_buffer(
+            &self.buffer,
+            allocation.offset * VERTEX_SIZE,
+            bytemuck::cast_slice(vertices),
+        );
+        Some(allocation)
+    }
+
+    pub fn free(&mut self, allocation: ArenaAllocation) {
+        self.allocator.free(allocation);
+    }
+
+    fn grow(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, at_least: u64) -> Option<()> {
+        let old = self.allocator.capacity();
+        // the new tail alone fits the upload, whatever is free before it
+        let capacity = (old * 2).max(old + at_least);
+        if capacity * VERTEX_SIZE > device.limits().max_buffer_size {
+            return None;
+        }
+        let buffer = Self::create_buffer(device, capacity);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Chunk Arena Grow Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, old * VERTEX_SIZE);
+        // pending writes to the old buffer land before the copy
+        queue.submit(std::iter::once(encoder.finish()));
+        self.buffer = buffer;
+        self.allocator.grow(capacity);
+        log::debug!(
+            "chunk arena grew to {} MiB",
+            capacity * VERTEX_SIZE / (1024 * 1024)
+        );
+        Some(())
+    }
+}
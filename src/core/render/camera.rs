@@ -73,6 +73,12 @@ impl Camera {
         self.update_matrices();
     }
 
+    pub fn set_far(&mut self, far: f32) {
+        self.far = far;
+        self.is_dirty = true;
+        self.update_matrices();
+    }
+
     fn update_matrices(&mut self) {
         if !self.is_dirty {
             return;
@@ -96,10 +102,10 @@ impl Camera {
     }
 
     pub fn get_uniform(&self) -> UniformBuffer {
-        let mut uniform = UniformBuffer::default();
-        uniform.view_proj = self.view_proj_matrix.into();
-        uniform.camera_pos = [self.pos.x, self.pos.y, self.pos.z, 69.0];
-        uniform
+        UniformBuffer {
+            view_proj: self.view_proj_matrix.into(),
+            camera_pos: [self.pos.x, self.pos.y, self.pos.z, 69.0],
+        }
     }
 
     /// (forward, right, up)
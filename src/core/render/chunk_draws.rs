@@ -0,0 +1,69 @@
+use std::ops::Range;
+
+use wgpu::util::DrawIndexedIndirectArgs;
+
+use crate::core::meshing::ChunkOrigin;
+
+/// Chunk draws of one frame. Every chunk adds its origin as one instance and a draw per run
+/// of visible direction ranges, drawn with one `multi_draw_indexed_indirect` or one by one.
+#[derive(Debug, Default)]
+pub struct DrawList {
+    pub instances: Vec<ChunkOrigin>,
+    pub draws: Vec<DrawIndexedIndirectArgs>,
+}
+
+impl DrawList {
+    pub fn clear(&mut self) {
+        self.instances.clear();
+        self.draws.clear();
+    }
+
+    /// `base_vertex` is where the chunk's vertices start in the arena, neighbouring visible
+    /// direction ranges share a draw.
+    pub fn push_chunk(
+        &mut self,
+        origin: ChunkOrigin,
+        base_vertex: i32,
+        direction_ranges: &[Range<u32>; 6],
+        facing: [bool; 6],
+    ) {
+        let instance = self.instances.len() as u32;
+        let first_draw = self.draws.len();
+        let mut pending: Option<Range<u32>> = None;
+        for (range, _) in direction_ranges
+            .iter()
+            .zip(facing)
+            .filter(|(range, facing)| *facing && !range.is_empty())
+        {
+            match &mut pending {
+                Some(run) if run.end == range.start => run.end = range.end,
+                _ => {
+                    if let Some(run) = pending.replace(range.clone()) {
+                        self.push_draw(run, base_vertex, instance);
+                    }
+                }
+            }
+        }
+        if let Some(run) = pending {
+            self.push_draw(run, base_vertex, instance);
+        }
+        if self.draws.len() > first_draw {
+            self.instances.push(origin);
+        }
+    }
+
+    fn push_draw(&mut self, indices: Range<u32>, base_vertex: i32, instance: u32) {
+        self.draws.push(DrawIndexedIndirectArgs {
+            index_count: indices.len() as u32,
+            instance_count: 1,
+            first_index: indices.start,
+            base_vertex,
+            first_instance: instance,
+        });
+    }
+
+    /// Indices submitted by all draws.
+    pub fn index_count(&self) -> u64 {
+        self.draws.iter().map(|draw| draw.index_count as u64).sum()
+    }
+}
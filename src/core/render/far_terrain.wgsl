@@ -0,0 +1,43 @@
+struct CameraUniforms {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec4<f32>,
+}
+
+// xy min and max in blocks of the columns real chunks already cover
+struct FarUniforms {
+    hole: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniforms;
+
+@group(1) @binding(0)
+var<uniform> far: FarUniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_xy: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(model: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(model.position, 1.0);
+    out.world_xy = model.position.xy;
+    out.color = model.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if all(in.world_xy >= far.hole.xy) && all(in.world_xy < far.hole.zw) {
+        discard;
+    }
+    return in.color;
+}
@@ -1,4 +1,4 @@
-use cgmath::{InnerSpace, Matrix4, Point3, Vector4};
+use cgmath::{InnerSpace, Matrix4, Point3, Vector3, Vector4};
 
 use crate::core::chunk::CHUNK_SIZE;
 
@@ -98,3 +98,27 @@ impl Frustum {
         self.intersects_aabb(chunk_aabb_min, chunk_aabb_max)
     }
 }
+
+/// Face directions of a chunk, in `Vertex::normal` order, with at least one face plane the
+/// camera is in front of. Groups facing away are backface culled as a whole, which only
+/// holds for `axis_aligned` meshes, tilted quads can face the camera from anywhere.
+pub fn facing_directions(
+    eye: Vector3<f32>,
+    world_pos: &(i64, i64, i64),
+    axis_aligned: bool,
+) -> [bool; 6] {
+    if !axis_aligned {
+        return [true; 6];
+    }
+    let size = CHUNK_SIZE as f32;
+    let origin = [world_pos.0, world_pos.1, world_pos.2].map(|c| c as f32 * size);
+    std::array::from_fn(|direction| {
+        let axis = direction / 2;
+        // block faces sit on half-block planes, the margins cover them with room to spare
+        if direction % 2 == 0 {
+            eye[axis] > origin[axis] - 1.0
+        } else {
+            eye[axis] < origin[axis] + size
+        }
+    })
+}
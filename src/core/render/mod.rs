@@ -1,4 +1,9 @@
+pub mod block_textures;
+pub mod buffer_arena;
 pub mod camera;
+pub mod chunk_draws;
 pub mod frustum_culling;
 pub mod renderer;
 pub mod texture_array;
+pub mod texture_atlas;
+pub mod upload_queue;
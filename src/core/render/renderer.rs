@@ -1,18 +1,23 @@
 use cgmath::{Vector2, Vector3};
 use hashbrown::HashMap;
+use std::ops::Range;
 use wgpu::util::DeviceExt;
 use wgpu::*;
 
 use crate::{
     core::{
-        chunk::Chunk,
-        meshing::{Mesh, Vertex},
+        chunk::CHUNK_SIZE,
+        meshing::{ChunkOrigin, FarTile, FarVertex, MAX_CHUNK_QUADS, Mesh, Vertex, quad_indices},
         render::{
+            block_textures::{BlockTextures, TextureMode},
+            buffer_arena::{ArenaAllocation, ChunkArena, INITIAL_ARENA_VERTICES},
             camera::{Camera, UniformBuffer},
-            texture_array::TextureArray,
+            chunk_draws::DrawList,
+            frustum_culling::facing_directions,
+            upload_queue::{DEFAULT_UPLOAD_BUDGET, UploadQueue},
         },
     },
-    world::World,
+    world::{World, mesh_sink::MeshSink},
 };
 
 const SKYBOX: Color = Color {
@@ -23,6 +28,8 @@ const SKYBOX: Color = Color {
 };
 const RENDER_LOGGING: bool = cfg!(debug_assertions);
 const INITIAL_MESH_CAPACITY: usize = 5_000;
+// chunk draws the per-frame instance and indirect buffers start out with
+const INITIAL_DRAW_CAPACITY: u64 = 4096;
 
 pub struct Renderer {
     pub device: Device,
@@ -35,22 +42,54 @@ pub struct Renderer {
     pub camera: Camera,
     depth_texture: TextureView, // Store view instead of texture
     pub texture_bind_group: BindGroup,
-    pub texture_array: TextureArray,
+    pub block_textures: BlockTextures,
     depth_texture_format: TextureFormat,
     mesh_cache: HashMap<(i64, i64, i64), GpuMesh>,
-    dirty_meshes: Vec<(i64, i64, i64)>,
+    // `QUAD_INDICES` for the largest possible chunk mesh, shared by every chunk draw
+    quad_index_buffer: Buffer,
+    chunk_arena: ChunkArena,
+    draw_list: DrawList,
+    // per-frame `ChunkOrigin` instances and indirect args of `draw_list`
+    instance_buffer: Buffer,
+    indirect_buffer: Buffer,
+    // one `multi_draw_indexed_indirect` per frame instead of a draw per chunk
+    multi_draw: bool,
+    // meshes waiting for the GPU, the rest of a frame's budget keeps drawing old vertices
+    upload_queue: UploadQueue,
+    upload_budget: usize,
+    far_pipeline: RenderPipeline,
+    far_uniform_buffer: Buffer,
+    far_bind_group: BindGroup,
+    far_tiles: HashMap<(i64, i64), GpuFarTile>,
+    dirty_far_tiles: Vec<(i64, i64)>,
+}
+
+struct GpuFarTile {
+    vertex_buffer: Buffer,
+    index_buffer: Buffer,
+    index_count: u32,
 }
 
 #[derive(Default)]
 struct GpuMesh {
-    vertex_buffer: Option<Buffer>,
-    index_buffer: Option<Buffer>,
+    // vertices in `chunk_arena`
+    allocation: Option<ArenaAllocation>,
     index_count: u32,
+    direction_ranges: [Range<u32>; 6],
+    axis_aligned: bool,
+    // `Mesh::version` of the uploaded vertices
     version: u32,
 }
 
 impl Renderer {
     pub async fn new(window: &'static winit::window::Window) -> Self {
+        Self::with_texture_mode(window, TextureMode::Auto).await
+    }
+
+    pub async fn with_texture_mode(
+        window: &'static winit::window::Window,
+        texture_mode: TextureMode,
+    ) -> Self {
         log::debug!("started renderer initialization...");
         let instance = Instance::new(&InstanceDescriptor {
             backends: Backends::PRIMARY,
@@ -69,8 +108,12 @@ impl Renderer {
         let (device, queue) = adapter
             .request_device(&DeviceDescriptor {
                 label: None,
-                required_features: Features::empty(),
-                required_limits: Limits::default(),
+                required_features: adapter.features() & Features::INDIRECT_FIRST_INSTANCE,
+                // the chunk arena grows as far as the adapter lets a buffer grow
+                required_limits: Limits {
+                    max_buffer_size: adapter.limits().max_buffer_size,
+                    ..Limits::default()
+                },
                 memory_hints: MemoryHints::default(),
                 experimental_features: ExperimentalFeatures::default(),
                 trace: Trace::default(),
@@ -138,45 +181,13 @@ impl Renderer {
             &include_bytes!("../../../assets/textures/grass.png")[..],
             // TODO
         ];
-        let texture_array =
-            TextureArray::new(&device, &queue, &texture_bytes, Some("block_textures")).unwrap();
-        let texture_bind_group_layout =
-            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-                entries: &[
-                    BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: ShaderStages::FRAGMENT,
-                        ty: BindingType::Texture {
-                            sample_type: TextureSampleType::Float { filterable: true },
-                            view_dimension: TextureViewDimension::D2Array,
-                            multisampled: false,
-                        },
-                        count: None,
-                    },
-                    BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: ShaderStages::FRAGMENT,
-                        ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                        count: None,
-                    },
-                ],
-                label: Some("texture_bind_group_layout"),
-            });
-
-        let texture_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::TextureView(&texture_array.view),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: BindingResource::Sampler(&texture_array.sampler),
-                },
-            ],
-            label: Some("texture_bind_group"),
-        });
+        let texture_mode = texture_mode
+            .resolve(&texture_bytes, device.limits().max_texture_array_layers)
+            .expect("Bundled block textures failed to decode");
+        log::debug!("block textures use {:?} mode", texture_mode);
+        let (block_textures, texture_bind_group_layout, texture_bind_group) =
+            BlockTextures::new(&device, &queue, &texture_bytes, texture_mode)
+                .expect("Failed to create block textures");
 
         let render_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
@@ -191,11 +202,11 @@ impl Renderer {
                 module: &shader,
                 entry_point: Some("vs_main"),
                 compilation_options: Default::default(),
-                buffers: &[Vertex::desc()],
+                buffers: &[Vertex::desc(), ChunkOrigin::desc()],
             },
             fragment: Some(FragmentState {
                 module: &shader,
-                entry_point: Some("fs_main"),
+                entry_point: Some(block_textures.fragment_entry_point()),
                 compilation_options: Default::default(),
                 targets: &[Some(ColorTargetState {
                     format: config.format,
@@ -224,6 +235,62 @@ impl Renderer {
             cache: None,
         });
 
+        let (far_pipeline, far_bind_group_layout) = Self::create_far_pipeline(
+            &device,
+            config.format,
+            depth_texture_format,
+            &uniform_bind_group_layout,
+        );
+        // zeroed hole covers nothing
+        let far_uniform_buffer = device.create_buffer_init(&util::BufferInitDescriptor {
+            label: Some("Far Terrain Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[0f32; 4]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let far_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &far_bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: far_uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("far_bind_group"),
+        });
+
+        let quad_index_buffer = device.create_buffer_init(&util::BufferInitDescriptor {
+            label: Some("Quad Index Buffer"),
+            contents: bytemuck::cast_slice(&quad_indices(MAX_CHUNK_QUADS)),
+            usage: BufferUsages::INDEX,
+        });
+        let chunk_arena = ChunkArena::new(&device, INITIAL_ARENA_VERTICES);
+        let instance_buffer = Self::create_draw_buffer(
+            &device,
+            "Chunk Instance Buffer",
+            INITIAL_DRAW_CAPACITY * std::mem::size_of::<ChunkOrigin>() as u64,
+            BufferUsages::VERTEX,
+        );
+        let indirect_buffer = Self::create_draw_buffer(
+            &device,
+            "Chunk Indirect Buffer",
+            INITIAL_DRAW_CAPACITY * std::mem::size_of::<util::DrawIndexedIndirectArgs>() as u64,
+            BufferUsages::INDIRECT,
+        );
+        // indirect draws carry each chunk's origin through `first_instance`
+        let multi_draw = device
+            .features()
+            .contains(Features::INDIRECT_FIRST_INSTANCE)
+            && adapter
+                .get_downlevel_capabilities()
+                .flags
+                .contains(DownlevelFlags::INDIRECT_EXECUTION);
+        log::info!(
+            "chunks are drawn {}",
+            if multi_draw {
+                "with multi-draw indirect"
+            } else {
+                "one draw at a time"
+            }
+        );
+
         let camera = Camera::new(
             Vector3::new(0.0, 0.0, 4.0),
             Vector2::new(0.0, 0.0),
@@ -244,13 +311,92 @@ impl Renderer {
             camera,
             depth_texture: depth_texture_view,
             texture_bind_group,
-            texture_array,
+            block_textures,
             depth_texture_format,
             mesh_cache: HashMap::with_capacity(INITIAL_MESH_CAPACITY),
-            dirty_meshes: Vec::new(),
+            quad_index_buffer,
+            chunk_arena,
+            draw_list: DrawList::default(),
+            instance_buffer,
+            indirect_buffer,
+            multi_draw,
+            upload_queue: UploadQueue::default(),
+            upload_budget: DEFAULT_UPLOAD_BUDGET,
+            far_pipeline,
+            far_uniform_buffer,
+            far_bind_group,
+            far_tiles: HashMap::new(),
+            dirty_far_tiles: Vec::new(),
         }
     }
 
+    /// Pipeline of the far terrain impostors, sharing the camera bind group layout.
+    fn create_far_pipeline(
+        device: &Device,
+        format: TextureFormat,
+        depth_format: TextureFormat,
+        camera_layout: &BindGroupLayout,
+    ) -> (RenderPipeline, BindGroupLayout) {
+        let far_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("far_bind_group_layout"),
+        });
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Far Terrain Shader"),
+            source: ShaderSource::Wgsl(include_str!("far_terrain.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Far Terrain Pipeline Layout"),
+            bind_group_layouts: &[camera_layout, &far_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Far Terrain Pipeline"),
+            layout: Some(&layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[FarVertex::desc()],
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                cull_mode: Some(Face::Back),
+                ..Default::default()
+            },
+            depth_stencil: Some(DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: true,
+                depth_compare: CompareFunction::Less,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        (pipeline, far_bind_group_layout)
+    }
+
     fn create_depth_texture(
         device: &Device,
         config: &SurfaceConfiguration,
@@ -287,11 +433,20 @@ impl Renderer {
             Self::create_depth_texture(&self.device, &self.config, self.depth_texture_format);
     }
 
-    pub fn render(&mut self, world: &World) -> Result<(), SurfaceError> {
+    pub fn render(&mut self, world: &mut World) -> Result<(), SurfaceError> {
         if RENDER_LOGGING {
             log::trace!("started render...");
         }
         self.process_dirty_meshes(world);
+        self.process_far_tiles(world);
+        self.build_draw_list();
+        let hole = world.far_terrain.hole();
+        let hole_rect = hole.map_or([0.0; 4], |(min, max)| [min[0], min[1], max[0], max[1]]);
+        self.queue.write_buffer(
+            &self.far_uniform_buffer,
+            0,
+            bytemuck::cast_slice(&hole_rect),
+        );
 
         let output = self.surface.get_current_texture()?;
         let view = output
@@ -331,23 +486,43 @@ impl Renderer {
             render_pass.set_pipeline(&self.render_pipeline);
             render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
             render_pass.set_bind_group(1, &self.texture_bind_group, &[]);
+            render_pass.set_index_buffer(self.quad_index_buffer.slice(..), IndexFormat::Uint32);
+
+            render_pass.set_vertex_buffer(0, self.chunk_arena.buffer.slice(..));
+            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+            if self.multi_draw {
+                render_pass.multi_draw_indexed_indirect(
+                    &self.indirect_buffer,
+                    0,
+                    self.draw_list.draws.len() as u32,
+                );
+            } else {
+                for draw in &self.draw_list.draws {
+                    render_pass.draw_indexed(
+                        draw.first_index..draw.first_index + draw.index_count,
+                        draw.base_vertex,
+                        draw.first_instance..draw.first_instance + 1,
+                    );
+                }
+            }
 
-            for (key, gpu_mesh) in &self.mesh_cache {
-                if let Some(chunk) = world.chunks.get(key) && chunk.is_dirty {
-                        continue;
-                    }
-
-                if !self.camera.frustum.check(key) {
+            // after the chunks, so fragments they cover fail the depth test early
+            render_pass.set_pipeline(&self.far_pipeline);
+            render_pass.set_bind_group(1, &self.far_bind_group, &[]);
+            for (key, tile) in &self.far_tiles {
+                let (min, max) = FarTile::bounds(*key);
+                let inside_hole = hole.is_some_and(|(hole_min, hole_max)| {
+                    min[0] >= hole_min[0]
+                        && min[1] >= hole_min[1]
+                        && max[0] <= hole_max[0]
+                        && max[1] <= hole_max[1]
+                });
+                if inside_hole {
                     continue;
                 }
-
-                if let (Some(vertex_buffer), Some(index_buffer)) =
-                    (&gpu_mesh.vertex_buffer, &gpu_mesh.index_buffer)
-                    && gpu_mesh.index_count > 0 {
-                        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                        render_pass.set_index_buffer(index_buffer.slice(..), IndexFormat::Uint32);
-                        render_pass.draw_indexed(0..gpu_mesh.index_count, 0, 0..1);
-                    }
+                render_pass.set_vertex_buffer(0, tile.vertex_buffer.slice(..));
+                render_pass.set_index_buffer(tile.index_buffer.slice(..), IndexFormat::Uint32);
+                render_pass.draw_indexed(0..tile.index_count, 0, 0..1);
             }
         }
 
@@ -356,51 +531,183 @@ impl Renderer {
         Ok(())
     }
 
-    fn process_dirty_meshes(&mut self, world: &World) {
-        if self.dirty_meshes.is_empty() {
-            return;
+    /// Collects this frame's chunk draws and uploads their instances and indirect args.
+    fn build_draw_list(&mut self) {
+        self.draw_list.clear();
+        for (key, gpu_mesh) in &self.mesh_cache {
+            // chunks being remeshed keep drawing their last uploaded mesh
+            let Some(allocation) = gpu_mesh.allocation else {
+                continue;
+            };
+            if gpu_mesh.index_count == 0 || !self.camera.frustum.check(key) {
+                continue;
+            }
+            // never uploaded, see `update_gpu_mesh`
+            let Some(origin) = ChunkOrigin::of_chunk(*key) else {
+                continue;
+            };
+            self.draw_list.push_chunk(
+                origin,
+                allocation.offset as i32,
+                &gpu_mesh.direction_ranges,
+                facing_directions(self.camera.pos, key, gpu_mesh.axis_aligned),
+            );
+        }
+
+        let instances = bytemuck::cast_slice(&self.draw_list.instances);
+        if instances.len() as u64 > self.instance_buffer.size() {
+            self.instance_buffer = Self::create_draw_buffer(
+                &self.device,
+                "Chunk Instance Buffer",
+                (instances.len() as u64).next_power_of_two(),
+                BufferUsages::VERTEX,
+            );
+        }
+        self.queue.write_buffer(&self.instance_buffer, 0, instances);
+        if self.multi_draw {
+            let draws = bytemuck::cast_slice(&self.draw_list.draws);
+            if draws.len() as u64 > self.indirect_buffer.size() {
+                self.indirect_buffer = Self::create_draw_buffer(
+                    &self.device,
+                    "Chunk Indirect Buffer",
+                    (draws.len() as u64).next_power_of_two(),
+                    BufferUsages::INDIRECT,
+                );
+            }
+            self.queue.write_buffer(&self.indirect_buffer, 0, draws);
         }
+    }
 
-        let dirty_meshes = std::mem::take(&mut self.dirty_meshes);
+    fn create_draw_buffer(device: &Device, label: &str, size: u64, usage: BufferUsages) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(label),
+            size,
+            usage: usage | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
 
-        for key in dirty_meshes {
-            if let Some(mesh) = world.meshes.get(&key) {
+    /// Uploads queued meshes until `upload_budget` bytes are spent, chunks in the frustum
+    /// first and nearest first among those.
+    fn process_dirty_meshes(&mut self, world: &mut World) {
+        let camera_chunk = (self.camera.pos / CHUNK_SIZE as f32).map(|v| v.floor() as i64);
+        let frustum = &self.camera.frustum;
+        let keys = self.upload_queue.take_frame(
+            self.upload_budget,
+            |key| {
+                let (dx, dy, dz) = (
+                    key.0 - camera_chunk.x,
+                    key.1 - camera_chunk.y,
+                    key.2 - camera_chunk.z,
+                );
+                (!frustum.check(key), dx * dx + dy * dy + dz * dz)
+            },
+            |key| {
+                world
+                    .meshes
+                    .get(key)
+                    .map(|mesh| std::mem::size_of_val(mesh.vertices.as_slice()))
+            },
+        );
+        for key in keys {
+            if let Some(mesh) = world.meshes.get_mut(&key) {
                 self.update_gpu_mesh(key, mesh);
             }
         }
     }
 
+    /// Builds buffers for far tiles changed since the last frame, replacing their old ones.
+    /// Tiles gone from the world by now are skipped, `far_tile_removed` dropped their buffers.
+    fn process_far_tiles(&mut self, world: &World) {
+        for key in std::mem::take(&mut self.dirty_far_tiles) {
+            let Some(tile) = world.far_terrain.tiles.get(&key) else {
+                continue;
+            };
+            let vertex_buffer = self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some(&format!("Far Vertex Buffer {:?}", key)),
+                contents: bytemuck::cast_slice(&tile.vertices),
+                usage: BufferUsages::VERTEX,
+            });
+            let index_buffer = self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some(&format!("Far Index Buffer {:?}", key)),
+                contents: bytemuck::cast_slice(&tile.indices),
+                usage: BufferUsages::INDEX,
+            });
+            self.far_tiles.insert(
+                key,
+                GpuFarTile {
+                    vertex_buffer,
+                    index_buffer,
+                    index_count: tile.indices.len() as u32,
+                },
+            );
+        }
+    }
+
     pub fn mark_mesh_dirty(&mut self, key: (i64, i64, i64)) {
-        self.dirty_meshes.push(key);
+        self.upload_queue.push(key);
     }
 
-    fn update_gpu_mesh(&mut self, key: (i64, i64, i64), mesh: &Mesh) {
+    /// Meshes waiting for an upload slot.
+    pub fn pending_uploads(&self) -> usize {
+        self.upload_queue.len()
+    }
+
+    /// Bytes of chunk vertices uploaded per frame, at least one mesh goes through regardless.
+    pub fn set_upload_budget(&mut self, bytes: usize) {
+        self.upload_budget = bytes;
+    }
+
+    /// Uploads the mesh and drops its CPU vertices, `write_buffer` has its own copy by then.
+    fn update_gpu_mesh(&mut self, key: (i64, i64, i64), mesh: &mut Mesh) {
+        if !mesh.is_dirty {
+            return;
+        }
+        if ChunkOrigin::of_chunk(key).is_none() {
+            log::warn!(
+                "chunk {:?} is beyond the drawable world extent, not drawn",
+                key
+            );
+            mesh.take_vertices();
+            return;
+        }
+        let vertices = mesh.take_vertices();
         let gpu_mesh = self.mesh_cache.entry(key).or_default();
-        if mesh.is_dirty && !mesh.vertices.is_empty() && !mesh.indices.is_empty() {
-            gpu_mesh.vertex_buffer =
-                Some(self.device.create_buffer_init(&util::BufferInitDescriptor {
-                    label: Some(&format!("Vertex Buffer {:?}", key)),
-                    contents: bytemuck::cast_slice(&mesh.vertices),
-                    usage: BufferUsages::VERTEX,
-                }));
-
-            gpu_mesh.index_buffer =
-                Some(self.device.create_buffer_init(&util::BufferInitDescriptor {
-                    label: Some(&format!("Index Buffer {:?}", key)),
-                    contents: bytemuck::cast_slice(&mesh.indices),
-                    usage: BufferUsages::INDEX,
-                }));
-
-            gpu_mesh.index_count = mesh.indices.len() as u32;
-            gpu_mesh.version += 1;
-
-            if RENDER_LOGGING {
-                log::trace!(
-                    "updated GPU mesh {:?} with {} indices",
-                    key,
-                    gpu_mesh.index_count
-                );
-            }
+        // a mesh that still fits its range is written over it, writes keep submission order
+        let reused = gpu_mesh
+            .allocation
+            .and_then(|allocation| self.chunk_arena.rewrite(&self.queue, allocation, &vertices));
+        if let Some(allocation) = gpu_mesh.allocation.take()
+            && reused.is_none()
+        {
+            self.chunk_arena.free(allocation);
+        }
+        // remeshed to nothing, the previous geometry must not linger
+        if vertices.is_empty() {
+            *gpu_mesh = GpuMesh {
+                version: mesh.version,
+                ..Default::default()
+            };
+            return;
+        }
+        gpu_mesh.allocation = reused.or_else(|| {
+            self.chunk_arena
+                .upload(&self.device, &self.queue, &vertices)
+        });
+        if gpu_mesh.allocation.is_none() {
+            log::warn!("chunk arena is full, mesh {:?} is not drawn", key);
+        }
+        gpu_mesh.index_count = mesh.index_count;
+        gpu_mesh.direction_ranges = mesh.direction_ranges.clone();
+        gpu_mesh.axis_aligned = mesh.axis_aligned;
+        gpu_mesh.version = mesh.version;
+
+        if RENDER_LOGGING {
+            log::trace!(
+                "updated GPU mesh {:?} with {} indices",
+                key,
+                gpu_mesh.index_count
+            );
         }
     }
 
@@ -420,13 +727,45 @@ impl Renderer {
             .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
     }
 
-    pub fn on_mesh_updated(&mut self, key: (i64, i64, i64)) {
+    /// Moves the far plane, frustum culling follows on the same frame.
+    pub fn set_far_plane(&mut self, far: f32) {
+        self.camera.set_far(far);
+        let uniform = self.camera.get_uniform();
+        self.queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// GPU bytes of the chunk vertices in use, the shared quad indices and the draw buffers.
+    pub fn mesh_cache_bytes(&self) -> u64 {
+        self.chunk_arena.used_bytes()
+            + self.quad_index_buffer.size()
+            + self.instance_buffer.size()
+            + self.indirect_buffer.size()
+    }
+}
+
+impl MeshSink for Renderer {
+    fn mesh_updated(&mut self, key: (i64, i64, i64)) {
         self.mark_mesh_dirty(key);
     }
 
-    pub fn cleanup_unused_meshes(&mut self, active_chunks: &HashMap<(i64, i64, i64), Chunk>) {
-        self.mesh_cache
-            .retain(|key, _| active_chunks.contains_key(key));
+    fn mesh_removed(&mut self, key: (i64, i64, i64)) {
+        self.upload_queue.remove(&key);
+        if let Some(allocation) = self
+            .mesh_cache
+            .remove(&key)
+            .and_then(|gpu_mesh| gpu_mesh.allocation)
+        {
+            self.chunk_arena.free(allocation);
+        }
+    }
+
+    fn far_tile_updated(&mut self, key: (i64, i64)) {
+        self.dirty_far_tiles.push(key);
+    }
+
+    fn far_tile_removed(&mut self, key: (i64, i64)) {
+        self.far_tiles.remove(&key);
     }
 }
 
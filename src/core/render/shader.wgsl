@@ -12,28 +12,64 @@ var texture_array: texture_2d_array<f32>;
 @group(1) @binding(1)
 var texture_sampler: sampler;
 
+// atlas mode, bound instead of `texture_array`
+@group(1) @binding(2)
+var atlas: texture_2d<f32>;
+
+// normalized x, y, width and height of each texture in the atlas, by texture id
+@group(1) @binding(3)
+var<uniform> atlas_rects: array<vec4<f32>, 256>;
+
+// see `Vertex` for the bit layout
 struct VertexInput {
-    @location(0) position: vec3<f32>,
-    @location(1) tex_coords: vec2<f32>,
-    @location(2) tex_id: u32,
+    @location(0) packed: vec2<u32>,
+}
+
+struct InstanceInput {
+    @location(1) chunk_origin: vec3<i32>,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
-    @location(1) tex_id: u32
+    @location(1) tex_id: u32,
+    @location(2) shade: f32,
 }
 
 @vertex
-fn vs_main(model: VertexInput) -> VertexOutput {
+fn vs_main(model: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let lo = model.packed.x;
+    let hi = model.packed.y;
+    // whole blocks shifted by one and a half, plus eighths for smooth meshes
+    let whole = vec3<u32>(lo & 63u, (lo >> 6u) & 63u, (lo >> 12u) & 63u);
+    let eighths = vec3<u32>((lo >> 23u) & 7u, (lo >> 26u) & 7u, (lo >> 29u) & 7u);
+    let local = vec3<f32>(whole) + vec3<f32>(eighths) / 8.0 - vec3<f32>(1.5);
+    let ao = (lo >> 21u) & 3u;
+
     var out: VertexOutput;
-    out.clip_position = camera.view_proj * vec4<f32>(model.position, 1.0);
-    out.tex_coords = model.tex_coords;
-    out.tex_id = model.tex_id;
+    let position = vec3<f32>(instance.chunk_origin) + local;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
+    out.tex_coords = vec2<f32>(f32(hi & 63u), f32((hi >> 6u) & 63u));
+    out.tex_id = (hi >> 12u) & 0xFFFFu;
+    // 3 is unoccluded, every occluding neighbour darkens the corner a step
+    out.shade = 0.4 + 0.2 * f32(ao);
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(texture_array, texture_sampler, in.tex_coords, i32(in.tex_id));
-}
\ No newline at end of file
+    let color = textureSample(texture_array, texture_sampler, in.tex_coords, i32(in.tex_id));
+    return vec4<f32>(color.rgb * in.shade, color.a);
+}
+
+@fragment
+fn fs_atlas(in: VertexOutput) -> @location(0) vec4<f32> {
+    let rect = atlas_rects[in.tex_id];
+    // repeats once per block inside the rect, gradients of the unwrapped coordinates
+    // keep the mip level from jumping at every block edge
+    let uv = rect.xy + fract(in.tex_coords) * rect.zw;
+    let dx = dpdx(in.tex_coords) * rect.zw;
+    let dy = dpdy(in.tex_coords) * rect.zw;
+    let color = textureSampleGrad(atlas, texture_sampler, uv, dx, dy);
+    return vec4<f32>(color.rgb * in.shade, color.a);
+}
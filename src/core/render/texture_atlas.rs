@@ -0,0 +1,245 @@
+use anyhow::*;
+use image::{RgbaImage, imageops};
+
+// pixels of each texture's edge repeated around it, keeps filtering and mips from bleeding
+const ATLAS_PADDING: u32 = 4;
+/// Entries of the shader's rect table, texture ids past it can't be addressed.
+pub const MAX_ATLAS_TEXTURES: usize = 256;
+
+/// Where one texture sits in the atlas, in pixels and without its padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Placement of every texture in a packed atlas, textures may differ in size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtlasLayout {
+    pub width: u32,
+    pub height: u32,
+    pub padding: u32,
+    /// in the order of the packed sizes, so indexed by texture id
+    pub rects: Vec<AtlasRect>,
+}
+
+impl AtlasLayout {
+    /// Shelf packing, tallest textures first. Both sides are powers of two and the width
+    /// doubles until everything fits below it, up to `max_size`.
+    pub fn pack(sizes: &[(u32, u32)], padding: u32, max_size: u32) -> Result<Self> {
+        ensure!(
+            sizes.len() <= MAX_ATLAS_TEXTURES,
+            "{} textures don't fit the atlas rect table of {}",
+            sizes.len(),
+            MAX_ATLAS_TEXTURES
+        );
+        let padded: Vec<_> = sizes
+            .iter()
+            .map(|&(w, h)| (w + 2 * padding, h + 2 * padding))
+            .collect();
+        let area: u64 = padded.iter().map(|&(w, h)| w as u64 * h as u64).sum();
+        let widest = padded.iter().map(|&(w, _)| w).max().unwrap_or(1);
+        let mut width = widest
+            .max((area as f64).sqrt().ceil() as u32)
+            .next_power_of_two();
+
+        let mut order: Vec<_> = (0..sizes.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(padded[i].1));
+        loop {
+            ensure!(
+                width <= max_size,
+                "textures don't fit an atlas of {max_size}x{max_size}"
+            );
+            let mut rects = vec![
+                AtlasRect {
+                    x: 0,
+                    y: 0,
+                    width: 0,
+                    height: 0,
+                };
+                sizes.len()
+            ];
+            let (mut x, mut shelf_y, mut shelf_height) = (0, 0, 0);
+            for &i in &order {
+                let (w, h) = padded[i];
+                if x + w > width {
+                    shelf_y += shelf_height;
+                    (x, shelf_height) = (0, 0);
+                }
+                rects[i] = AtlasRect {
+                    x: x + padding,
+                    y: shelf_y + padding,
+                    width: sizes[i].0,
+                    height: sizes[i].1,
+                };
+                x += w;
+                shelf_height = shelf_height.max(h);
+            }
+            let height = (shelf_y + shelf_height).max(1).next_power_of_two();
+            if height <= width {
+                return Ok(Self {
+                    width,
+                    height,
+                    padding,
+                    rects,
+                });
+            }
+            width *= 2;
+        }
+    }
+
+    /// Normalized x, y, width and height of every texture, the shader's rect table.
+    pub fn uv_rects(&self) -> Vec<[f32; 4]> {
+        let (w, h) = (self.width as f32, self.height as f32);
+        self.rects
+            .iter()
+            .map(|rect| {
+                [
+                    rect.x as f32 / w,
+                    rect.y as f32 / h,
+                    rect.width as f32 / w,
+                    rect.height as f32 / h,
+                ]
+            })
+            .collect()
+    }
+
+    /// Copies `images` to their rects and extends each edge into the padding.
+    pub fn compose(&self, images: &[RgbaImage]) -> RgbaImage {
+        let mut atlas = RgbaImage::new(self.width, self.height);
+        let pad = self.padding as i64;
+        for (rect, image) in self.rects.iter().zip(images) {
+            debug_assert_eq!(image.dimensions(), (rect.width, rect.height));
+            for y in -pad..rect.height as i64 + pad {
+                for x in -pad..rect.width as i64 + pad {
+                    let src_x = x.clamp(0, rect.width as i64 - 1) as u32;
+                    let src_y = y.clamp(0, rect.height as i64 - 1) as u32;
+                    atlas.put_pixel(
+                        (rect.x as i64 + x) as u32,
+                        (rect.y as i64 + y) as u32,
+                        *image.get_pixel(src_x, src_y),
+                    );
+                }
+            }
+        }
+        atlas
+    }
+}
+
+/// Block textures packed into one 2D texture, for backends with few array layers
+/// and packs with mixed texture sizes. Quads tile their texture per block in the
+/// shader through the rect table in `rect_buffer`.
+pub struct TextureAtlas {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub layout: AtlasLayout,
+    /// `MAX_ATLAS_TEXTURES` normalized rects, unused entries zeroed
+    pub rect_buffer: wgpu::Buffer,
+}
+
+impl TextureAtlas {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        textures_data: &[&[u8]],
+        label: Option<&str>,
+    ) -> Result<Self> {
+        let images = textures_data
+            .iter()
+            .map(|bytes| Ok(image::load_from_memory(bytes)?.to_rgba8()))
+            .collect::<Result<Vec<_>>>()?;
+        let sizes: Vec<_> = images.iter().map(|image| image.dimensions()).collect();
+        let layout = AtlasLayout::pack(
+            &sizes,
+            ATLAS_PADDING,
+            device.limits().max_texture_dimension_2d,
+        )?;
+        let atlas = layout.compose(&images);
+
+        // below this level the padding shrinks under a pixel and neighbours bleed in
+        let smallest = sizes.iter().map(|&(w, h)| w.min(h)).min().unwrap_or(1);
+        let mip_level_count = (ATLAS_PADDING.ilog2() + 1).min(smallest.max(1).ilog2() + 1);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: wgpu::Extent3d {
+                width: layout.width,
+                height: layout.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        for mip_level in 0..mip_level_count {
+            let (width, height) = (
+                (layout.width >> mip_level).max(1),
+                (layout.height >> mip_level).max(1),
+            );
+            let level = if mip_level == 0 {
+                atlas.clone()
+            } else {
+                imageops::resize(&atlas, width, height, imageops::FilterType::Triangle)
+            };
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &level,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * width),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // tiling happens in the shader, sampling never wraps around the atlas
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: mip_level_count as f32,
+            ..Default::default()
+        });
+
+        let mut rects = vec![[0f32; 4]; MAX_ATLAS_TEXTURES];
+        for (slot, rect) in rects.iter_mut().zip(layout.uv_rects()) {
+            *slot = rect;
+        }
+        let rect_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Atlas Rect Buffer"),
+                contents: bytemuck::cast_slice(&rects),
+                usage: wgpu::BufferUsages::UNIFORM,
+            },
+        );
+
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+            layout,
+            rect_buffer,
+        })
+    }
+}
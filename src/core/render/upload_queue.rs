@@ -0,0 +1,58 @@
+use hashbrown::HashSet;
+
+/// Bytes of chunk vertices uploaded per frame unless `Renderer::set_upload_budget` says otherwise.
+pub const DEFAULT_UPLOAD_BUDGET: usize = 4 * 1024 * 1024;
+
+/// Chunk meshes waiting for the GPU. Every frame takes the most urgent ones until the byte
+/// budget is spent, at least one so a mesh larger than the budget still gets through.
+#[derive(Debug, Default)]
+pub struct UploadQueue {
+    pending: HashSet<(i64, i64, i64)>,
+}
+
+impl UploadQueue {
+    /// Queued chunks are only uploaded once, with whatever mesh they have by then.
+    pub fn push(&mut self, key: (i64, i64, i64)) {
+        self.pending.insert(key);
+    }
+
+    pub fn remove(&mut self, key: &(i64, i64, i64)) {
+        self.pending.remove(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// This frame's uploads in order, lowest `priority` first. `bytes` is `None` for chunks
+    /// whose mesh is gone, those leave the queue without costing anything.
+    pub fn take_frame<P: Ord>(
+        &mut self,
+        budget: usize,
+        priority: impl Fn(&(i64, i64, i64)) -> P,
+        bytes: impl Fn(&(i64, i64, i64)) -> Option<usize>,
+    ) -> Vec<(i64, i64, i64)> {
+        let mut order: Vec<_> = self.pending.iter().copied().collect();
+        order.sort_by_cached_key(|key| priority(key));
+
+        let mut spent = 0;
+        let mut frame = Vec::new();
+        for key in order {
+            let Some(size) = bytes(&key) else {
+                self.pending.remove(&key);
+                continue;
+            };
+            if !frame.is_empty() && spent + size > budget {
+                break;
+            }
+            spent += size;
+            self.pending.remove(&key);
+            frame.push(key);
+        }
+        frame
+    }
+}
@@ -0,0 +1,79 @@
+use cgmath::Vector3;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use crate::{core::chunk::Chunk, world::worker_pool::WorkerPool};
+
+/// Identifies one load request of a chunk position.
+/// A cancelled token makes the worker skip the job, and a result whose `generation`
+/// no longer matches the one the world is waiting for is dropped on arrival.
+#[derive(Clone, Debug)]
+pub struct LoadToken {
+    pub generation: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LoadToken {
+    pub fn new(generation: u64) -> Self {
+        Self {
+            generation,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+pub struct GenerationJob {
+    pub key: (i64, i64, i64),
+    pub seed: u32,
+    pub token: LoadToken,
+}
+
+pub struct GeneratedChunk {
+    pub key: (i64, i64, i64),
+    pub generation: u64,
+    pub chunk: Chunk,
+}
+
+/// Persistent pool of terrain generation threads, skips jobs cancelled while queued.
+pub type ChunkWorkers = WorkerPool<GenerationJob, GeneratedChunk>;
+
+impl Default for ChunkWorkers {
+    fn default() -> Self {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get().saturating_sub(1))
+            .unwrap_or(1)
+            .max(1);
+        Self::new(threads)
+    }
+}
+
+impl ChunkWorkers {
+    pub fn new(worker_count: usize) -> Self {
+        Self::spawn("chunk-gen", worker_count, generate)
+    }
+}
+
+fn generate(job: GenerationJob) -> Option<GeneratedChunk> {
+    if job.token.is_cancelled() {
+        return None;
+    }
+    let (x, y, z) = job.key;
+    Some(GeneratedChunk {
+        key: job.key,
+        generation: job.token.generation,
+        chunk: Chunk::terrain_gen(Vector3 { x, y, z }, job.seed),
+    })
+}
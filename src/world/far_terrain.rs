@@ -0,0 +1,131 @@
+use hashbrown::HashMap;
+
+use crate::{
+    core::{
+        chunk::CHUNK_SIZE,
+        meshing::{FAR_TILE_CHUNKS, FarTile},
+    },
+    world::{World, mesh_sink::MeshSink},
+};
+
+// tiles built per update, nearest first
+const FAR_TILES_PER_UPDATE: usize = 4;
+
+/// Heightmap impostors for the terrain past the loaded chunks, around the first observer.
+/// Real chunks take over ring by ring: the `hole` covers the complete rings of columns whose
+/// surface chunks are meshed, and the renderer discards impostor fragments inside it.
+pub struct FarTerrain {
+    seed: u32,
+    pub tiles: HashMap<(i64, i64), FarTile>,
+    // observer column the covered rings are counted around
+    column: Option<(i64, i64)>,
+    // complete rings of meshed columns around `column`, 0 while the center one isn't meshed
+    covered_rings: i64,
+}
+
+impl FarTerrain {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            tiles: HashMap::new(),
+            column: None,
+            covered_rings: 0,
+        }
+    }
+
+    /// Quads along a tile edge for a tile `distance` tiles from the observer's one.
+    pub fn grid_for(distance: i64) -> usize {
+        match distance {
+            0..=1 => 32,
+            2..=3 => 16,
+            _ => 8,
+        }
+    }
+
+    /// World-space xy rectangle, min and max in blocks, where real chunks replace the impostors.
+    pub fn hole(&self) -> Option<([f32; 2], [f32; 2])> {
+        let column = self.column?;
+        if self.covered_rings == 0 {
+            return None;
+        }
+        let (r, size) = (self.covered_rings - 1, CHUNK_SIZE as i64);
+        // blocks are centered on integers, a column reaches half a block past its edge blocks
+        let edge = |c: i64| (c * size) as f32 - 0.5;
+        Some((
+            [edge(column.0 - r), edge(column.1 - r)],
+            [edge(column.0 + r + 1), edge(column.1 + r + 1)],
+        ))
+    }
+}
+
+impl World {
+    /// Builds and drops far terrain tiles around the first observer and fits the hole to
+    /// the columns real chunks cover.
+    pub fn update_far_terrain(&mut self, sink: &mut impl MeshSink) {
+        let Some(observer) = self.observers.first() else {
+            return;
+        };
+        let chunk = observer.chunk();
+        let column = (chunk.x, chunk.y);
+        let radius = (self.settings.far_distance + FAR_TILE_CHUNKS - 1) / FAR_TILE_CHUNKS;
+        let center = (
+            column.0.div_euclid(FAR_TILE_CHUNKS),
+            column.1.div_euclid(FAR_TILE_CHUNKS),
+        );
+        let distance = |key: &(i64, i64)| (key.0 - center.0).abs().max((key.1 - center.1).abs());
+
+        let far = &mut self.far_terrain;
+        let removed: Vec<_> = far
+            .tiles
+            .keys()
+            .filter(|key| radius == 0 || distance(key) > radius)
+            .copied()
+            .collect();
+        for key in removed {
+            far.tiles.remove(&key);
+            sink.far_tile_removed(key);
+        }
+
+        if radius > 0 {
+            let mut missing: Vec<_> = (center.0 - radius..=center.0 + radius)
+                .flat_map(|x| (center.1 - radius..=center.1 + radius).map(move |y| (x, y)))
+                .map(|key| (distance(&key), key))
+                .filter(|(distance, key)| {
+                    far.tiles.get(key).map(|tile| tile.grid)
+                        != Some(FarTerrain::grid_for(*distance))
+                })
+                .collect();
+            missing.sort_unstable();
+            for (distance, key) in missing.into_iter().take(FAR_TILES_PER_UPDATE) {
+                let tile = FarTile::build(far.seed, key, FarTerrain::grid_for(distance));
+                far.tiles.insert(key, tile);
+                sink.far_tile_updated(key);
+            }
+        }
+
+        // recounted every update, columns evicted or unloaded since show the impostors again
+        let mut rings = 0;
+        while rings <= self.settings.load_distance && self.ring_covered(column, rings) {
+            rings += 1;
+        }
+        self.far_terrain.column = Some(column);
+        self.far_terrain.covered_rings = rings;
+    }
+
+    /// Whether every column `ring` columns around `center` has its surface chunks meshed.
+    fn ring_covered(&self, center: (i64, i64), ring: i64) -> bool {
+        let Some(surface) = self.need_to_load.surface() else {
+            return false;
+        };
+        let meshed = |x: i64, y: i64| {
+            surface.window(x, y).is_some_and(|window| {
+                (window.min_z..=window.max_z).all(|z| self.meshes.contains_key(&(x, y, z)))
+            })
+        };
+        (center.0 - ring..=center.0 + ring).all(|x| {
+            (center.1 - ring..=center.1 + ring)
+                .filter(|y| (x - center.0).abs() == ring || (y - center.1).abs() == ring)
+                .all(|y| meshed(x, y))
+        })
+    }
+}
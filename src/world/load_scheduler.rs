@@ -0,0 +1,376 @@
+use cgmath::{InnerSpace, Vector3};
+use hashbrown::{HashMap, HashSet};
+use std::collections::BinaryHeap;
+
+use crate::world::surface_map::{SurfaceMap, SurfaceWindow};
+
+// queue is re-sorted once the view turned further than this since the last sort
+const REPRIORITIZE_DOT: f32 = 0.95;
+// or once the center drifted this many chunks away from the one priorities were computed for
+const REPRIORITIZE_DISTANCE: i64 = 4;
+
+/// Load area of one observer, in chunks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadView {
+    pub center: Vector3<i64>,
+    pub dir: Vector3<f32>,
+    pub radius: i64,
+    pub radius_z: i64,
+}
+
+impl LoadView {
+    pub fn new(center: Vector3<i64>, dir: Vector3<f32>, radius: i64, radius_z: i64) -> Self {
+        Self {
+            center,
+            dir,
+            radius,
+            radius_z,
+        }
+    }
+
+    fn same_area(&self, other: &LoadView) -> bool {
+        self.center == other.center
+            && self.radius == other.radius
+            && self.radius_z == other.radius_z
+    }
+
+    fn overlaps(&self, other: &LoadView) -> bool {
+        let delta = self.center - other.center;
+        delta.x.abs() <= 2 * self.radius
+            && delta.y.abs() <= 2 * self.radius
+            && delta.z.abs() <= 2 * self.radius_z
+    }
+
+    fn contains_column(&self, x: i64, y: i64) -> bool {
+        (x - self.center.x).abs() <= self.radius && (y - self.center.y).abs() <= self.radius
+    }
+}
+
+/// Incremental queue of chunk positions waiting to be generated, for any number of views.
+/// Only positions that enter a view's area when its center moves are scanned,
+/// positions that left every area are pruned lazily on pop and on the next re-sort.
+///
+/// Each area covers `radius` columns around its center. Vertically it spans `radius_z`
+/// around the center and, with a surface map, the window around each column's terrain surface.
+pub struct LoadScheduler {
+    // `None` until the first update, everything is in range before that
+    views: Option<Vec<LoadView>>,
+    // views the priorities in `queue` were computed for
+    queue_views: Vec<LoadView>,
+    pending: HashSet<(i64, i64, i64)>,
+    // positions held by load tickets, wanted regardless of the views
+    forced: HashMap<(i64, i64, i64), u32>,
+    // lazily pruned, entries not in `pending` are skipped on pop
+    queue: BinaryHeap<(u32, (i64, i64, i64))>,
+    surface: Option<SurfaceMap>,
+}
+
+impl Default for LoadScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoadScheduler {
+    pub fn new() -> Self {
+        Self {
+            views: None,
+            queue_views: Vec::new(),
+            pending: HashSet::new(),
+            forced: HashMap::new(),
+            queue: BinaryHeap::new(),
+            surface: None,
+        }
+    }
+
+    /// Follows the terrain surface vertically instead of loading only around the centers.
+    pub fn with_surface(self, surface: SurfaceMap) -> Self {
+        Self {
+            surface: Some(surface),
+            ..self
+        }
+    }
+
+    pub fn surface(&self) -> Option<&SurfaceMap> {
+        self.surface.as_ref()
+    }
+
+    /// Views of the last update, `None` before the first one.
+    pub fn views(&self) -> Option<&[LoadView]> {
+        self.views.as_deref()
+    }
+
+    /// May still count positions that left every area since the last re-sort.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn contains(&self, key: &(i64, i64, i64)) -> bool {
+        self.pending.contains(key) && self.is_wanted(key)
+    }
+
+    /// Inside any view's area or held by a load ticket.
+    pub fn is_wanted(&self, key: &(i64, i64, i64)) -> bool {
+        self.in_range(key) || self.forced.contains_key(key)
+    }
+
+    /// Whether `key` is inside any view's area, always true before the first update.
+    pub fn in_range(&self, key: &(i64, i64, i64)) -> bool {
+        match &self.views {
+            Some(views) => Self::in_any_area(self.surface.as_ref(), views, key),
+            None => true,
+        }
+    }
+
+    pub fn push(&mut self, key: (i64, i64, i64)) {
+        if self.pending.insert(key) {
+            let priority = self.priority(key);
+            self.queue.push((priority, key));
+        }
+    }
+
+    /// Keeps `key` wanted with at least `priority` until `unforce`, and queues it unless
+    /// `is_known` holds for it.
+    pub fn force(
+        &mut self,
+        key: (i64, i64, i64),
+        priority: u32,
+        is_known: impl Fn(&(i64, i64, i64)) -> bool,
+    ) {
+        let forced = self.forced.entry(key).or_insert(priority);
+        *forced = (*forced).max(priority);
+        if is_known(&key) {
+            return;
+        }
+        let priority = self.priority(key);
+        self.pending.insert(key);
+        // may duplicate an entry already in the heap, the stale one is skipped on pop
+        self.queue.push((priority, key));
+    }
+
+    pub fn unforce(&mut self, key: &(i64, i64, i64)) {
+        self.forced.remove(key);
+    }
+
+    pub fn pop(&mut self) -> Option<(i64, i64, i64)> {
+        while let Some((_, key)) = self.queue.pop() {
+            if self.pending.remove(&key) && self.is_wanted(&key) {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Drops pending positions, ticket-held ones stay forced and come back on the next rescan.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.queue.clear();
+    }
+
+    /// Moves the load areas to `views`, matched to the previous views by index.
+    /// A view that crossed a chunk boundary only scans its newly entered slabs, added or
+    /// resized views scan their whole area. Positions for which `is_known` holds are skipped.
+    /// Returns whether any area changed.
+    pub fn update(
+        &mut self,
+        views: &[LoadView],
+        is_known: impl Fn(&(i64, i64, i64)) -> bool,
+    ) -> bool {
+        let Some(old) = self.views.replace(views.to_vec()) else {
+            self.rescan(views, is_known);
+            return true;
+        };
+        let mut changed = old.len() != views.len();
+        for (i, view) in views.iter().enumerate() {
+            match old.get(i) {
+                Some(prev) if prev.same_area(view) => {}
+                Some(prev)
+                    if prev.radius == view.radius
+                        && prev.radius_z == view.radius_z
+                        && prev.overlaps(view) =>
+                {
+                    self.enqueue_entered(prev, view, &is_known);
+                    changed = true;
+                }
+                _ => {
+                    // new, resized or teleported view, nothing to reuse
+                    self.scan_view(view, &is_known);
+                    changed = true;
+                }
+            }
+        }
+
+        let stale = self.queue_views.len() != views.len()
+            || self.queue_views.iter().zip(views).any(|(queued, view)| {
+                let drift = view.center - queued.center;
+                drift.x.abs().max(drift.y.abs()).max(drift.z.abs()) >= REPRIORITIZE_DISTANCE
+                    || queued.dir.dot(view.dir) < REPRIORITIZE_DOT
+            });
+        if stale {
+            self.rebuild_queue();
+        }
+        changed
+    }
+
+    /// Drops everything and scans the whole area of every view.
+    pub fn rescan(&mut self, views: &[LoadView], is_known: impl Fn(&(i64, i64, i64)) -> bool) {
+        self.clear();
+        self.views = Some(views.to_vec());
+        self.queue_views = views.to_vec();
+        let forced: Vec<_> = self.forced.keys().copied().collect();
+        for key in forced {
+            if !is_known(&key) {
+                self.push(key);
+            }
+        }
+        for view in views {
+            self.scan_view(view, &is_known);
+        }
+    }
+
+    fn scan_view(&mut self, view: &LoadView, is_known: &impl Fn(&(i64, i64, i64)) -> bool) {
+        let (center, r) = (view.center, view.radius);
+        for x in center.x - r..=center.x + r {
+            for y in center.y - r..=center.y + r {
+                let window = self.column_window(x, y);
+                for z in Self::column_z_range(view, window) {
+                    let key = (x, y, z);
+                    if Self::wanted_in_column(view, window, z) && !is_known(&key) {
+                        self.push(key);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pushes positions inside the area of `new` that were outside the area of `old`.
+    fn enqueue_entered(
+        &mut self,
+        old: &LoadView,
+        new: &LoadView,
+        is_known: &impl Fn(&(i64, i64, i64)) -> bool,
+    ) {
+        let (center, r) = (new.center, new.radius);
+        for x in center.x - r..=center.x + r {
+            for y in center.y - r..=center.y + r {
+                let column_inside_old = old.contains_column(x, y);
+                let window = self.column_window(x, y);
+                for z in Self::column_z_range(new, window) {
+                    if !Self::wanted_in_column(new, window, z) {
+                        continue;
+                    }
+                    // whole column is new unless x and y were already covered
+                    if column_inside_old && Self::wanted_in_column(old, window, z) {
+                        continue;
+                    }
+                    let key = (x, y, z);
+                    if !is_known(&key) {
+                        self.push(key);
+                    }
+                }
+            }
+        }
+    }
+
+    fn column_window(&mut self, x: i64, y: i64) -> Option<SurfaceWindow> {
+        self.surface.as_mut().map(|surface| surface.estimate(x, y))
+    }
+
+    /// Smallest z range covering both the view's span and the surface window.
+    fn column_z_range(
+        view: &LoadView,
+        window: Option<SurfaceWindow>,
+    ) -> std::ops::RangeInclusive<i64> {
+        let (lo, hi) = (view.center.z - view.radius_z, view.center.z + view.radius_z);
+        match window {
+            Some(window) => lo.min(window.min_z)..=hi.max(window.max_z),
+            None => lo..=hi,
+        }
+    }
+
+    fn wanted_in_column(view: &LoadView, window: Option<SurfaceWindow>, z: i64) -> bool {
+        (z - view.center.z).abs() <= view.radius_z
+            || window.is_some_and(|window| window.contains(z))
+    }
+
+    fn rebuild_queue(&mut self) {
+        if let Some(views) = &self.views {
+            self.queue_views = views.clone();
+            let (forced, surface) = (&self.forced, self.surface.as_ref());
+            self.pending
+                .retain(|key| Self::in_any_area(surface, views, key) || forced.contains_key(key));
+            // windows are kept out to twice the radius, where chunks are usually still loaded
+            if let Some(surface) = self.surface.as_mut() {
+                surface.retain(|x, y| {
+                    views.iter().any(|view| {
+                        (x - view.center.x).abs() <= 2 * view.radius
+                            && (y - view.center.y).abs() <= 2 * view.radius
+                    })
+                });
+            }
+        }
+        let entries: Vec<_> = self
+            .pending
+            .iter()
+            .map(|&key| (self.priority(key), key))
+            .collect();
+        self.queue = BinaryHeap::from(entries);
+    }
+
+    /// Highest priority of the views whose area holds `key`, or of its ticket.
+    fn priority(&self, key: (i64, i64, i64)) -> u32 {
+        let pos = Vector3::new(key.0, key.1, key.2);
+        let view = self
+            .views
+            .iter()
+            .flatten()
+            .filter(|view| Self::in_area(self.surface.as_ref(), view, &key))
+            .map(|view| Self::loading_priority(pos, view.center, view.dir))
+            .max()
+            .unwrap_or(0);
+        match self.forced.get(&key) {
+            Some(&forced) => forced.max(view),
+            None => view,
+        }
+    }
+
+    fn in_any_area(
+        surface: Option<&SurfaceMap>,
+        views: &[LoadView],
+        key: &(i64, i64, i64),
+    ) -> bool {
+        views.iter().any(|view| Self::in_area(surface, view, key))
+    }
+
+    fn in_area(surface: Option<&SurfaceMap>, view: &LoadView, key: &(i64, i64, i64)) -> bool {
+        view.contains_column(key.0, key.1)
+            && ((key.2 - view.center.z).abs() <= view.radius_z
+                || surface
+                    .and_then(|surface| surface.window(key.0, key.1))
+                    .is_some_and(|window| window.contains(key.2)))
+    }
+
+    pub fn loading_priority(
+        chunk_pos: Vector3<i64>,
+        player_chunk: Vector3<i64>,
+        camera_dir: Vector3<f32>,
+    ) -> u32 {
+        let delta = chunk_pos - player_chunk;
+        let distance_sq = delta.x * delta.x + delta.y * delta.y + delta.z * delta.z;
+        // Base priority: closer chunks have higher priority
+        let mut priority: u32 = (2000 - distance_sq).max(0) as u32;
+        // Boost priority for chunks in camera direction
+        let chunk_dir = Vector3::new(delta.x as f32, delta.y as f32, delta.z as f32).normalize();
+        let dot = camera_dir.dot(chunk_dir);
+        priority += (1000.0 * dot.max(0.0)) as u32;
+        // Boost for chunks directly around player (neighbors)
+        if delta.x.abs() <= 5 && delta.z.abs() <= 5 && delta.y.abs() <= 5 {
+            priority += 1000;
+        }
+        priority
+    }
+}
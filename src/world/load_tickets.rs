@@ -0,0 +1,148 @@
+use cgmath::Vector3;
+use hashbrown::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::world::World;
+
+// view priorities are in 0..~4000, this puts tickets ahead of everything but the player's surroundings
+const DEFAULT_TICKET_PRIORITY: u32 = 3000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TicketId(u64);
+
+/// Request to keep a cube of chunks loaded, independent of where the camera is.
+/// Used for the spawn area, scripted locations or simulation regions.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadTicket {
+    pub center: Vector3<i64>,
+    /// in chunks, per axis
+    pub radius: i64,
+    pub priority: u32,
+    pub expires_at: Option<Instant>,
+}
+
+impl LoadTicket {
+    pub fn new(center: Vector3<i64>, radius: i64) -> Self {
+        Self {
+            center,
+            radius: radius.max(0),
+            priority: DEFAULT_TICKET_PRIORITY,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_priority(self, priority: u32) -> Self {
+        Self { priority, ..self }
+    }
+
+    pub fn with_expiry(self, lifetime: Duration) -> Self {
+        Self {
+            expires_at: Some(Instant::now() + lifetime),
+            ..self
+        }
+    }
+
+    pub fn covers(&self, key: &(i64, i64, i64)) -> bool {
+        (key.0 - self.center.x).abs() <= self.radius
+            && (key.1 - self.center.y).abs() <= self.radius
+            && (key.2 - self.center.z).abs() <= self.radius
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (i64, i64, i64)> + use<> {
+        let (c, r) = (self.center, self.radius);
+        (c.x - r..=c.x + r).flat_map(move |x| {
+            (c.y - r..=c.y + r).flat_map(move |y| (c.z - r..=c.z + r).map(move |z| (x, y, z)))
+        })
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+#[derive(Default)]
+pub struct LoadTickets {
+    tickets: HashMap<TicketId, LoadTicket>,
+    next_id: u64,
+}
+
+impl LoadTickets {
+    pub fn insert(&mut self, ticket: LoadTicket) -> TicketId {
+        let id = TicketId(self.next_id);
+        self.next_id += 1;
+        self.tickets.insert(id, ticket);
+        id
+    }
+
+    pub fn remove(&mut self, id: TicketId) -> Option<LoadTicket> {
+        self.tickets.remove(&id)
+    }
+
+    pub fn get(&self, id: TicketId) -> Option<&LoadTicket> {
+        self.tickets.get(&id)
+    }
+
+    pub fn covers(&self, key: &(i64, i64, i64)) -> bool {
+        self.tickets.values().any(|ticket| ticket.covers(key))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tickets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tickets.is_empty()
+    }
+
+    /// Removes and returns tickets that expired by `now`.
+    pub fn take_expired(&mut self, now: Instant) -> Vec<LoadTicket> {
+        let expired: Vec<_> = self
+            .tickets
+            .iter()
+            .filter(|(_, ticket)| ticket.is_expired(now))
+            .map(|(&id, _)| id)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| self.tickets.remove(&id))
+            .collect()
+    }
+}
+
+impl World {
+    /// Keeps the ticket's area loaded until it is removed or expires. Loaded and in-flight
+    /// positions are forced as well, that keeps their jobs from being cancelled.
+    pub fn add_ticket(&mut self, ticket: LoadTicket) -> TicketId {
+        let is_known = Self::known_to_loader(&self.chunks, &self.in_flight, &self.evicted);
+        for key in ticket.positions() {
+            self.need_to_load.force(key, ticket.priority, &is_known);
+        }
+        self.tickets.insert(ticket)
+    }
+
+    pub fn remove_ticket(&mut self, id: TicketId) {
+        if let Some(ticket) = self.tickets.remove(id) {
+            self.release_ticket_areas(&[ticket]);
+        }
+    }
+
+    pub fn expire_tickets(&mut self, now: Instant) {
+        let expired = self.tickets.take_expired(now);
+        if !expired.is_empty() {
+            self.release_ticket_areas(&expired);
+        }
+    }
+
+    /// Positions only these tickets held are handed back to the view distance rules.
+    fn release_ticket_areas(&mut self, released: &[LoadTicket]) {
+        for key in released.iter().flat_map(LoadTicket::positions) {
+            if !self.tickets.covers(&key) {
+                self.need_to_load.unforce(&key);
+            }
+        }
+        self.cancel_out_of_range();
+        if self.need_to_load.views().is_some() {
+            self.unload_far();
+        }
+    }
+}
@@ -1,121 +1,172 @@
-use cgmath::{InnerSpace, Vector3};
-use rayon::prelude::*;
-use std::time::{Duration, Instant};
-
-use crate::{
-    core::{
-        chunk::{CHUNK_SIZE, Chunk},
-        render::camera::Camera,
-    },
-    world::World,
+use cgmath::Vector3;
+use hashbrown::HashMap;
+use std::{collections::HashSet, time::Instant};
+
+use crate::core::{chunk::Chunk, chunk_state::ChunkState};
+use crate::world::{
+    World,
+    chunk_workers::{GeneratedChunk, GenerationJob, LoadToken},
+    observer::Observer,
+    settings::WorldSettings,
 };
 
-const LOAD_DISTANCE: i32 = 20;
-const LOAD_DISTANCE_Z: i32 = 5;
-const UNLOAD_DISTANCE: i32 = LOAD_DISTANCE * 2;
+// upper bound of jobs handed to the workers but not integrated yet
+const MAX_IN_FLIGHT_JOBS: usize = 64;
 
 impl World {
-    pub fn loader_update(&mut self, has_time: Duration, camera: &Camera) {
-        let pos = camera.pos;
-        let (forward, _, _) = camera.fru();
-        let chunk_idx = Vector3::new(
-            (pos.x as i64).div_euclid(CHUNK_SIZE as i64),
-            (pos.y as i64).div_euclid(CHUNK_SIZE as i64),
-            (pos.z as i64).div_euclid(CHUNK_SIZE as i64),
+    /// Loads around every observer, priorities are merged across them and a chunk stays
+    /// loaded while any observer or ticket needs it.
+    pub fn loader_update(&mut self, observers: &[Observer]) {
+        self.expire_tickets(Instant::now());
+        let views: Vec<_> = observers.iter().map(Observer::load_view).collect();
+        let area_changed = self.need_to_load.update(
+            &views,
+            Self::known_to_loader(&self.chunks, &self.in_flight, &self.evicted),
         );
-
-        self.collect_around(chunk_idx, forward);
-        self.unload_far(chunk_idx);
-
-        const LOAD_RATIO: f64 = 0.7;
-        let load_time = Duration::from_secs_f64(has_time.as_secs_f64() * LOAD_RATIO);
-
-        self.load_new(load_time);
-        // self.update_chunk_states(renderer);
+        let unload_key = |observer: &Observer| {
+            (
+                observer.chunk(),
+                observer.unload_distance,
+                observer.unload_distance_z,
+            )
+        };
+        let moved = area_changed
+            || self.observers.len() != observers.len()
+            || self
+                .observers
+                .iter()
+                .zip(observers)
+                .any(|(old, new)| unload_key(old) != unload_key(new));
+        self.observers = observers.to_vec();
+        if moved {
+            self.cancel_out_of_range();
+            self.unload_far();
+        }
+        self.load_new();
     }
 
-    pub fn load_new(&mut self, has_time: Duration) {
-        let started = Instant::now();
-        const BATCH_SIZE: usize = 30;
-
-        while started.elapsed() < has_time && !self.need_to_load.is_empty() {
-            let batch: Vec<_> = self
-                .need_to_load
-                .drain(..BATCH_SIZE.min(self.need_to_load.len()))
-                .collect();
+    /// Positions the scheduler skips: loaded, being generated, or evicted until visible again.
+    pub fn known_to_loader<'a>(
+        chunks: &'a HashMap<(i64, i64, i64), Chunk>,
+        in_flight: &'a HashMap<(i64, i64, i64), LoadToken>,
+        evicted: &'a HashSet<(i64, i64, i64)>,
+    ) -> impl Fn(&(i64, i64, i64)) -> bool + 'a {
+        |key| chunks.contains_key(key) || in_flight.contains_key(key) || evicted.contains(key)
+    }
 
-            let new_chunks: Vec<((i64, i64, i64), Chunk)> = batch
-                .par_iter()
-                .map(|&(x, y, z)| {
-                    let world_pos = Vector3 { x, y, z };
-                    let chunk = Chunk::terrain_gen(world_pos, self.seed);
-                    ((x, y, z), chunk)
-                })
-                .collect();
+    /// Integrates finished chunks and tops the workers up to `MAX_IN_FLIGHT_JOBS`.
+    /// Never blocks, generation itself happens on the worker threads.
+    pub fn load_new(&mut self) {
+        while let Some(generated) = self.workers.try_recv() {
+            self.integrate_generated(generated);
+        }
+        self.dispatch_jobs();
+    }
 
-            for (key, chunk) in new_chunks {
-                self.chunks.insert(key, chunk);
-                self.mark_neighbors_dirty(key);
+    /// Blocks until everything in `need_to_load` is generated and integrated.
+    /// Meant for benches, tests and initial spawn, not for the frame loop.
+    pub fn finish_loading(&mut self) {
+        loop {
+            self.dispatch_jobs();
+            if self.in_flight.is_empty() {
+                break;
+            }
+            match self.workers.recv() {
+                Some(generated) => self.integrate_generated(generated),
+                None => break,
             }
         }
     }
 
-    pub fn collect_around(&mut self, player_chunk: Vector3<i64>, camera_dir: Vector3<f32>) {
-        use std::cmp::Reverse;
-        let mut chunks_to_load = Vec::new();
-        for x in -LOAD_DISTANCE..=LOAD_DISTANCE {
-            for y in -LOAD_DISTANCE..=LOAD_DISTANCE {
-                for z in -LOAD_DISTANCE_Z..=LOAD_DISTANCE_Z {
-                    let chunk_pos = player_chunk + Vector3::new(x as i64, y as i64, z as i64);
-                    let key = (chunk_pos.x, chunk_pos.y, chunk_pos.z);
+    fn dispatch_jobs(&mut self) {
+        while self.in_flight.len() < MAX_IN_FLIGHT_JOBS {
+            let Some(key) = self.need_to_load.pop() else {
+                break;
+            };
+            if self.chunks.contains_key(&key) || self.in_flight.contains_key(&key) {
+                continue;
+            }
+            let token = LoadToken::new(self.next_load_generation);
+            self.next_load_generation += 1;
+            self.in_flight.insert(key, token.clone());
+            self.workers.submit(GenerationJob {
+                key,
+                seed: self.seed,
+                token,
+            });
+        }
+    }
 
-                    if !self.chunks.contains_key(&key) && !self.need_to_load.contains(&key) {
-                        let priority =
-                            self.calculate_loading_priority(chunk_pos, player_chunk, camera_dir);
-                        chunks_to_load.push((priority, key));
-                    }
-                }
+    fn integrate_generated(&mut self, generated: GeneratedChunk) {
+        let key = generated.key;
+        // cancelled or superseded by a newer request of the same position
+        match self.in_flight.get(&key) {
+            Some(token) if token.generation == generated.generation => {
+                self.in_flight.remove(&key);
             }
+            _ => return,
         }
-        chunks_to_load.sort_by_key(|(priority, _)| Reverse(*priority));
-        // self.need_to_load.extend(chunks_to_load.into_iter().map(|(_, key)| key));
-        self.need_to_load = chunks_to_load.into_iter().map(|(_, key)| key).collect();
+        self.insert_generated(key, generated.chunk);
     }
 
-    fn calculate_loading_priority(
-        &self,
-        chunk_pos: Vector3<i64>,
-        player_chunk: Vector3<i64>,
-        camera_dir: Vector3<f32>,
-    ) -> u32 {
-        let delta = chunk_pos - player_chunk;
-        let distance_sq = delta.x * delta.x + delta.y * delta.y + delta.z * delta.z;
-        // Base priority: closer chunks have higher priority
-        let mut priority: u32 = (2000 - distance_sq).max(0) as u32;
-        // Boost priority for chunks in camera direction
-        let chunk_dir = Vector3::new(delta.x as f32, delta.y as f32, delta.z as f32).normalize();
-        let dot = camera_dir.dot(chunk_dir);
-        priority += (1000.0 * dot.max(0.0)) as u32;
-        // Boost for chunks directly around player (neighbors)
-        if delta.x.abs() <= 5 && delta.z.abs() <= 5 && delta.y.abs() <= 5 {
-            priority += 1000;
+    /// Applies new distances right away: the observers of the last `loader_update` take them
+    /// over, their areas are rescanned and jobs and chunks outside of them are cancelled and
+    /// unloaded.
+    pub fn set_settings(&mut self, settings: WorldSettings) {
+        let remesh = settings.mesher != self.settings.mesher;
+        self.settings = settings.sanitized();
+        for observer in &mut self.observers {
+            observer.set_distances(&self.settings);
+        }
+        if self.need_to_load.views().is_some() {
+            let views: Vec<_> = self.observers.iter().map(Observer::load_view).collect();
+            self.need_to_load.rescan(
+                &views,
+                Self::known_to_loader(&self.chunks, &self.in_flight, &self.evicted),
+            );
+            self.cancel_out_of_range();
+            self.unload_far();
+        }
+        // LOD rings may have moved as well
+        self.lod_centers.clear();
+        if remesh {
+            let keys = self
+                .chunks
+                .iter()
+                .filter(|(_, chunk)| chunk.is_meshable() || chunk.state() == ChunkState::Meshing)
+                .map(|(key, _)| *key);
+            self.dirty_chunks.extend(keys);
         }
-        priority
     }
 
-    pub fn unload_far(&mut self, player_chunk: Vector3<i64>) {
-        let unload_dist_sq = (UNLOAD_DISTANCE * UNLOAD_DISTANCE) as i64;
-        let unload_dist_z = LOAD_DISTANCE_Z as i64 + 10;
+    /// Cancels in-flight jobs that left the scheduler's load box and aren't held by a ticket.
+    pub fn cancel_out_of_range(&mut self) {
+        let scheduler = &self.need_to_load;
+        self.in_flight.retain(|key, token| {
+            let in_range = scheduler.is_wanted(key);
+            if !in_range {
+                token.cancel();
+            }
+            in_range
+        });
+    }
 
+    /// Unloads chunks no observer or ticket needs anymore.
+    pub fn unload_far(&mut self) {
+        let surface = self.need_to_load.surface();
         let to_remove: Vec<_> = self
             .chunks
             .keys()
             .filter(|&&(x, y, z)| {
-                let dx = x - player_chunk.x;
-                let dy = y - player_chunk.y;
-                let dz = z - player_chunk.z;
-                (dx * dx + dy * dy) > unload_dist_sq || dz.abs() > unload_dist_z
+                let near_surface = surface
+                    .and_then(|surface| surface.window(x, y))
+                    .is_some_and(|window| window.contains(z));
+                !self
+                    .observers
+                    .iter()
+                    .any(|observer| observer.keeps(&(x, y, z), near_surface))
             })
+            .filter(|key| !self.tickets.covers(key))
             .cloned()
             .collect();
 
@@ -140,8 +191,12 @@ impl World {
                 center.1 + offset.1,
                 center.2 + offset.2,
             );
-            if let Some(chunk) = self.chunks.get_mut(&neighbor) {
-                chunk.is_dirty = true;
+            if self
+                .chunks
+                .get(&neighbor)
+                // in-flight meshes were captured with the old border, they are sent again
+                .is_some_and(|chunk| chunk.is_meshable() || chunk.state() == ChunkState::Meshing)
+            {
                 self.dirty_chunks.insert(neighbor);
             }
         }
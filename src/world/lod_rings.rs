@@ -0,0 +1,54 @@
+use cgmath::Vector3;
+
+use crate::{
+    core::meshing::Lod,
+    world::{World, observer::Observer},
+};
+
+impl World {
+    /// Level of detail for the chunk at `key`, from its distance to the nearest observer.
+    /// Full resolution while there are no observers.
+    pub fn lod_for(&self, key: &(i64, i64, i64)) -> Lod {
+        let Some(distance_sq) = self
+            .observers
+            .iter()
+            .map(|observer| {
+                let delta = Vector3::new(key.0, key.1, key.2) - observer.chunk();
+                delta.x * delta.x + delta.y * delta.y + delta.z * delta.z
+            })
+            .min()
+        else {
+            return Lod::FULL;
+        };
+        let level = self
+            .settings
+            .lod_distances
+            .iter()
+            .filter(|&&distance| distance_sq > distance * distance)
+            .count();
+        Lod::new(level as u8)
+    }
+
+    /// Re-picks every chunk's level once an observer crossed a chunk boundary. Chunks whose
+    /// level changed are remeshed together with their face neighbours, which seal the seam.
+    pub fn update_lods(&mut self) {
+        let centers: Vec<_> = self.observers.iter().map(Observer::chunk).collect();
+        if centers == self.lod_centers {
+            return;
+        }
+        self.lod_centers = centers;
+        let changed: Vec<_> = self
+            .chunks
+            .iter()
+            .map(|(key, chunk)| (*key, chunk.lod, self.lod_for(key)))
+            .filter(|(_, old, new)| old != new)
+            .collect();
+        for (key, _, lod) in changed {
+            if let Some(chunk) = self.chunks.get_mut(&key) {
+                chunk.lod = lod;
+            }
+            self.dirty_chunks.insert(key);
+            self.mark_neighbors_dirty(key);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    core::{chunk_state::ChunkState, render::frustum_culling::Frustum},
+    world::World,
+};
+
+/// Bytes held by the world's chunk storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub chunk_bytes: usize,
+    pub mesh_bytes: usize,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.chunk_bytes + self.mesh_bytes
+    }
+}
+
+impl World {
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            chunk_bytes: self.chunks.values().map(|chunk| chunk.memory_bytes()).sum(),
+            mesh_bytes: self.meshes.values().map(|mesh| mesh.memory_bytes()).sum(),
+        }
+    }
+
+    /// Stamps chunks inside the frustum with a new frame, and queues evicted positions again
+    /// once they come back into view. Without a frustum nothing is visible, chunks still age
+    /// so the memory budget keeps evicting.
+    pub fn update_visibility(&mut self, frustum: Option<&Frustum>) {
+        self.visibility_frame += 1;
+        let frame = self.visibility_frame;
+        let visible = |key: &(i64, i64, i64)| frustum.is_some_and(|frustum| frustum.check(key));
+        for (key, chunk) in self.chunks.iter_mut() {
+            if visible(key) {
+                chunk.last_visible = frame;
+            }
+        }
+        let scheduler = &mut self.need_to_load;
+        self.evicted.retain(|key| {
+            if !scheduler.is_wanted(key) {
+                return false;
+            }
+            if visible(key) {
+                scheduler.push(*key);
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Evicts least recently visible chunks until the budget from the settings is met.
+    /// Chunks visible this frame and ticket-held ones are never evicted.
+    pub fn enforce_memory_budget(&mut self) {
+        let Some(budget) = self.settings.memory_budget else {
+            self.over_memory_budget = false;
+            return;
+        };
+        let mut usage = self.memory_usage().total();
+        if usage <= budget {
+            self.over_memory_budget = false;
+            return;
+        }
+
+        let mut candidates: Vec<_> = self
+            .chunks
+            .iter()
+            .filter(|(key, chunk)| {
+                chunk.last_visible < self.visibility_frame
+                    && chunk.state() != ChunkState::Unloading
+                    && !self.tickets.covers(key)
+            })
+            .map(|(&key, chunk)| (chunk.last_visible, key))
+            .collect();
+        candidates.sort_unstable();
+
+        for (_, key) in candidates {
+            if usage <= budget {
+                break;
+            }
+            let chunk_bytes = self
+                .chunks
+                .get(&key)
+                .map_or(0, |chunk| chunk.memory_bytes());
+            let mesh_bytes = self.meshes.get(&key).map_or(0, |mesh| mesh.memory_bytes());
+            usage -= chunk_bytes + mesh_bytes;
+            self.drop_chunk(key.into());
+            self.evicted.insert(key);
+        }
+        let over = usage > budget;
+        if over && !self.over_memory_budget {
+            log::debug!(
+                "memory budget of {} bytes exceeded by visible chunks ({} bytes)",
+                budget,
+                usage
+            );
+        }
+        self.over_memory_budget = over;
+    }
+}
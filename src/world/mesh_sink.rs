@@ -0,0 +1,51 @@
+/// Receives the world's mesh changes. The renderer uploads them to the GPU, headless
+/// servers and tests use `NullMeshSink` or `RecordingMeshSink` instead.
+pub trait MeshSink {
+    /// `World::meshes[key]` was created or replaced.
+    fn mesh_updated(&mut self, key: (i64, i64, i64));
+    /// The chunk at `key` was unloaded together with its mesh.
+    fn mesh_removed(&mut self, key: (i64, i64, i64));
+    /// `World::far_terrain` built or rebuilt the tile at `key`, in tile coordinates.
+    fn far_tile_updated(&mut self, _key: (i64, i64)) {}
+    /// The far terrain tile at `key` left the far distance.
+    fn far_tile_removed(&mut self, _key: (i64, i64)) {}
+}
+
+/// Ignores every change.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullMeshSink;
+
+impl MeshSink for NullMeshSink {
+    fn mesh_updated(&mut self, _key: (i64, i64, i64)) {}
+
+    fn mesh_removed(&mut self, _key: (i64, i64, i64)) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshEvent {
+    Updated((i64, i64, i64)),
+    Removed((i64, i64, i64)),
+}
+
+/// Keeps every change in order, for tests and tooling.
+#[derive(Debug, Default, Clone)]
+pub struct RecordingMeshSink {
+    pub events: Vec<MeshEvent>,
+}
+
+impl RecordingMeshSink {
+    /// Returns the recorded events and starts over.
+    pub fn take(&mut self) -> Vec<MeshEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl MeshSink for RecordingMeshSink {
+    fn mesh_updated(&mut self, key: (i64, i64, i64)) {
+        self.events.push(MeshEvent::Updated(key));
+    }
+
+    fn mesh_removed(&mut self, key: (i64, i64, i64)) {
+        self.events.push(MeshEvent::Removed(key));
+    }
+}
@@ -0,0 +1,53 @@
+use std::thread;
+
+use crate::{
+    core::meshing::{ChunkSnapshot, MesherKind, Vertex},
+    world::worker_pool::WorkerPool,
+};
+
+pub struct MeshJob {
+    pub key: (i64, i64, i64),
+    pub generation: u64,
+    pub snapshot: ChunkSnapshot,
+    pub mesher: MesherKind,
+}
+
+pub struct MeshedChunk {
+    pub key: (i64, i64, i64),
+    pub generation: u64,
+    pub vertices: Vec<Vertex>,
+    /// see `ChunkMesher::axis_aligned`
+    pub axis_aligned: bool,
+}
+
+/// Persistent pool of meshing threads, fed with snapshots so the workers never touch the world.
+/// Finished meshes come back through a queue and are swapped in by the main thread,
+/// the previous mesh stays in place until then.
+pub type MeshWorkers = WorkerPool<MeshJob, MeshedChunk>;
+
+impl Default for MeshWorkers {
+    fn default() -> Self {
+        // shares the cores with the generation workers, meshing is the cheaper half
+        let threads = thread::available_parallelism()
+            .map(|n| n.get() / 2)
+            .unwrap_or(1)
+            .max(1);
+        Self::new(threads)
+    }
+}
+
+impl MeshWorkers {
+    pub fn new(worker_count: usize) -> Self {
+        Self::spawn("chunk-mesh", worker_count, mesh)
+    }
+}
+
+fn mesh(job: MeshJob) -> Option<MeshedChunk> {
+    let mesher = job.mesher.mesher();
+    Some(MeshedChunk {
+        key: job.key,
+        generation: job.generation,
+        vertices: mesher.mesh(&job.snapshot),
+        axis_aligned: mesher.axis_aligned(),
+    })
+}
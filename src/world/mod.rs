@@ -4,4 +4,16 @@ mod world;
 pub use terrain_generator::TerrainGenerator;
 pub use world::World;
 
+pub mod chunk_workers;
+pub mod far_terrain;
+pub mod load_scheduler;
+pub mod load_tickets;
 pub mod loading_managment;
+pub mod lod_rings;
+pub mod memory_budget;
+pub mod mesh_sink;
+pub mod mesh_workers;
+pub mod observer;
+pub mod settings;
+pub mod surface_map;
+pub mod worker_pool;
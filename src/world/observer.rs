@@ -0,0 +1,70 @@
+use cgmath::Vector3;
+
+use crate::{
+    core::{chunk::CHUNK_SIZE, render::camera::Camera},
+    world::{load_scheduler::LoadView, settings::WorldSettings},
+};
+
+/// Anything chunks are loaded for: the local camera, a split-screen player,
+/// a remote player on a server or a spectator tool. Distances are in chunks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    /// in blocks
+    pub position: Vector3<f32>,
+    pub direction: Vector3<f32>,
+    pub load_distance: i64,
+    pub load_distance_z: i64,
+    pub unload_distance: i64,
+    pub unload_distance_z: i64,
+}
+
+impl Observer {
+    /// Takes the distances from `settings`.
+    pub fn new(position: Vector3<f32>, direction: Vector3<f32>, settings: &WorldSettings) -> Self {
+        let settings = settings.sanitized();
+        Self {
+            position,
+            direction,
+            load_distance: settings.load_distance,
+            load_distance_z: settings.load_distance_z,
+            unload_distance: settings.unload_distance,
+            unload_distance_z: settings.unload_distance_z,
+        }
+    }
+
+    /// Replaces the distances with the ones from `settings`.
+    pub fn set_distances(&mut self, settings: &WorldSettings) {
+        *self = Self::new(self.position, self.direction, settings);
+    }
+
+    pub fn from_camera(camera: &Camera, settings: &WorldSettings) -> Self {
+        let (forward, _, _) = camera.fru();
+        Self::new(camera.pos, forward, settings)
+    }
+
+    pub fn chunk(&self) -> Vector3<i64> {
+        Vector3::new(
+            (self.position.x.floor() as i64).div_euclid(CHUNK_SIZE as i64),
+            (self.position.y.floor() as i64).div_euclid(CHUNK_SIZE as i64),
+            (self.position.z.floor() as i64).div_euclid(CHUNK_SIZE as i64),
+        )
+    }
+
+    pub fn load_view(&self) -> LoadView {
+        LoadView::new(
+            self.chunk(),
+            self.direction,
+            self.load_distance,
+            self.load_distance_z,
+        )
+    }
+
+    /// Whether this observer still needs the chunk at `key`, with `near_surface` telling
+    /// if it lies in its column's surface window.
+    pub fn keeps(&self, key: &(i64, i64, i64), near_surface: bool) -> bool {
+        let chunk = self.chunk();
+        let (dx, dy, dz) = (key.0 - chunk.x, key.1 - chunk.y, key.2 - chunk.z);
+        dx * dx + dy * dy <= self.unload_distance * self.unload_distance
+            && (dz.abs() <= self.unload_distance_z || near_surface)
+    }
+}
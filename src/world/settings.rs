@@ -0,0 +1,100 @@
+use crate::core::{chunk::CHUNK_SIZE, meshing::MesherKind};
+
+/// View and simulation distances in chunks, the chunk memory budget and the chunk mesher.
+/// Applied at runtime through `World::set_settings`, the renderer takes `far_plane` from here.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldSettings {
+    pub load_distance: i64,
+    /// around the camera, columns also load a window around their terrain surface
+    pub load_distance_z: i64,
+    pub unload_distance: i64,
+    pub unload_distance_z: i64,
+    /// distances from the nearest observer where chunk meshes switch to 2×, 4× and 8× downsampling
+    pub lod_distances: [i64; 3],
+    /// heightmap impostors stand in for the terrain out to here, 0 turns them off
+    pub far_distance: i64,
+    /// bytes of chunk data and CPU-side meshes, least recently visible chunks are evicted above it
+    pub memory_budget: Option<usize>,
+    pub mesher: MesherKind,
+}
+
+impl Default for WorldSettings {
+    fn default() -> Self {
+        Self {
+            load_distance: 20,
+            load_distance_z: 5,
+            unload_distance: 40,
+            unload_distance_z: 15,
+            lod_distances: [8, 12, 16],
+            far_distance: 256,
+            memory_budget: None,
+            mesher: MesherKind::default(),
+        }
+    }
+}
+
+impl WorldSettings {
+    /// Keeps the default ratio of unloading at twice the load distance.
+    pub fn with_load_distance(self, load_distance: i64) -> Self {
+        Self {
+            load_distance,
+            unload_distance: load_distance * 2,
+            ..self
+        }
+        .sanitized()
+    }
+
+    pub fn with_lod_distances(self, lod_distances: [i64; 3]) -> Self {
+        Self {
+            lod_distances,
+            ..self
+        }
+        .sanitized()
+    }
+
+    pub fn with_far_distance(self, far_distance: i64) -> Self {
+        Self {
+            far_distance,
+            ..self
+        }
+        .sanitized()
+    }
+
+    pub fn with_memory_budget(self, memory_budget: Option<usize>) -> Self {
+        Self {
+            memory_budget,
+            ..self
+        }
+    }
+
+    pub fn with_mesher(self, mesher: MesherKind) -> Self {
+        Self { mesher, ..self }
+    }
+
+    /// Clamps distances to at least one chunk, never unloads inside the load box
+    /// and keeps LOD rings in ascending order.
+    pub fn sanitized(self) -> Self {
+        let load_distance = self.load_distance.max(1);
+        let load_distance_z = self.load_distance_z.max(1);
+        let [lod_2, lod_4, lod_8] = self.lod_distances;
+        let lod_2 = lod_2.max(1);
+        let lod_4 = lod_4.max(lod_2);
+        Self {
+            load_distance,
+            load_distance_z,
+            unload_distance: self.unload_distance.max(load_distance),
+            unload_distance_z: self.unload_distance_z.max(load_distance_z),
+            lod_distances: [lod_2, lod_4, lod_8.max(lod_4)],
+            far_distance: self.far_distance.max(0),
+            memory_budget: self.memory_budget,
+            mesher: self.mesher,
+        }
+    }
+
+    /// Far plane that still covers every chunk kept loaded and the far terrain.
+    pub fn far_plane(&self) -> f32 {
+        let xy = (self.unload_distance.max(self.far_distance) + 1) as f32;
+        let z = (self.unload_distance_z + 1) as f32;
+        (xy * xy + z * z).sqrt() * CHUNK_SIZE as f32
+    }
+}
@@ -0,0 +1,105 @@
+use hashbrown::HashMap;
+
+use crate::{
+    core::chunk::CHUNK_SIZE,
+    world::terrain_generator::{SCALE_Z, TerrainGenerator},
+};
+
+// chunks of slack above and below the sampled height range, covers peaks between samples
+const SURFACE_MARGIN: i64 = 1;
+// corners, edge midpoints and center of a column
+const SAMPLE_OFFSETS: [i64; 3] = [0, CHUNK_SIZE as i64 / 2, CHUNK_SIZE as i64 - 1];
+
+/// What an ungenerated chunk outside its column's surface window is known to contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkFill {
+    Empty,
+    Solid,
+}
+
+/// Range of chunk z around the terrain surface of one column, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceWindow {
+    pub min_z: i64,
+    pub max_z: i64,
+}
+
+impl SurfaceWindow {
+    pub fn contains(&self, z: i64) -> bool {
+        (self.min_z..=self.max_z).contains(&z)
+    }
+}
+
+/// Per column surface estimates sampled from the generator's 2D height, no chunks are generated.
+/// Two integers per column is all it takes to know every chunk above is air and every chunk below is solid.
+pub struct SurfaceMap {
+    seed: u32,
+    columns: HashMap<(i64, i64), SurfaceWindow>,
+}
+
+impl SurfaceMap {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            columns: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self, x: i64, y: i64) -> Option<SurfaceWindow> {
+        self.columns.get(&(x, y)).copied()
+    }
+
+    /// Cached window of the column, sampled on first use.
+    pub fn estimate(&mut self, x: i64, y: i64) -> SurfaceWindow {
+        let seed = self.seed;
+        *self
+            .columns
+            .entry((x, y))
+            .or_insert_with(|| Self::sample(seed, x, y))
+    }
+
+    /// `None` inside the window or for columns never estimated.
+    pub fn fill(&self, key: &(i64, i64, i64)) -> Option<ChunkFill> {
+        let window = self.window(key.0, key.1)?;
+        if key.2 > window.max_z {
+            Some(ChunkFill::Empty)
+        } else if key.2 < window.min_z {
+            Some(ChunkFill::Solid)
+        } else {
+            None
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(i64, i64) -> bool) {
+        self.columns.retain(|&(x, y), _| keep(x, y));
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    fn sample(seed: u32, x: i64, y: i64) -> SurfaceWindow {
+        let base = (x * CHUNK_SIZE as i64, y * CHUNK_SIZE as i64);
+        let points = SAMPLE_OFFSETS.iter().flat_map(|dx| {
+            SAMPLE_OFFSETS
+                .iter()
+                .map(move |dy| (base.0 + dx, base.1 + dy))
+        });
+        let (min, max) = TerrainGenerator::surface_heights(seed, points)
+            .fold((f32::MAX, f32::MIN), |(min, max), h| {
+                (min.min(h), max.max(h))
+            });
+
+        let to_chunk = |h: f32| (h.floor() as i64).div_euclid(CHUNK_SIZE as i64);
+        // generator places nothing above SCALE_Z and only stone below -SCALE_Z
+        let (bottom, top) = (to_chunk(-SCALE_Z), to_chunk(SCALE_Z));
+        SurfaceWindow {
+            min_z: (to_chunk(min) - SURFACE_MARGIN).max(bottom),
+            max_z: (to_chunk(max) + SURFACE_MARGIN).min(top),
+        }
+    }
+}
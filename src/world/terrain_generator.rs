@@ -6,35 +6,58 @@ use crate::core::{
     chunk::{CHUNK_SIZE, CHUNK_VOLUME, Chunk},
 };
 
+const FRACTAL_SCALE_XY: f32 = 0.0005;
+const WARP_SCALE_XY: f32 = 0.0015;
+/// terrain height never leaves -SCALE_Z..SCALE_Z blocks
+pub const SCALE_Z: f32 = 150.0;
+
 pub struct TerrainGenerator;
 
 impl TerrainGenerator {
+    fn warp_noise(seed: u32) -> FastNoiseLite {
+        let mut warp_gen = FastNoiseLite::with_seed(seed as i32);
+        warp_gen.set_frequency(Some(WARP_SCALE_XY));
+        warp_gen.set_domain_warp_amp(Some(600.0));
+        warp_gen
+    }
+
+    fn height_noise(seed: u32) -> FastNoiseLite {
+        let mut noise_gen = FastNoiseLite::with_seed(seed as i32);
+        noise_gen.set_fractal_type(Some(FractalType::FBm));
+        noise_gen.set_fractal_octaves(Some(7));
+        noise_gen.set_frequency(Some(FRACTAL_SCALE_XY));
+        noise_gen
+    }
+
+    /// Samples of the 2D surface height in blocks, same function `heightmap_advanced` fills below.
+    /// Noise generators are built once for all `points`.
+    pub fn surface_heights(
+        seed: u32,
+        points: impl IntoIterator<Item = (i64, i64)>,
+    ) -> impl Iterator<Item = f32> {
+        let warp_gen = Self::warp_noise(seed);
+        let noise_gen = Self::height_noise(seed);
+        points.into_iter().map(move |(x, y)| {
+            let (x_warp, y_warp) = warp_gen.domain_warp_2d(x as f32, y as f32);
+            noise_gen.get_noise_2d(x_warp, y_warp) * SCALE_Z
+        })
+    }
+
     pub fn heightmap_advanced(
         world_pos: &Vector3<i64>,
         seed: u32,
         blocks: &mut [Block; CHUNK_VOLUME],
     ) {
-        const FRACTAL_SCALE_XY: f32 = 0.0005;
-        const WARP_SCALE_XY: f32 = 0.0015;
-        const SCALE_Z: f32 = 150.0;
         if (world_pos.z * CHUNK_SIZE as i64) > SCALE_Z as i64 {
             return;
         }
         if ((world_pos.z + 1) * CHUNK_SIZE as i64) < -SCALE_Z as i64 {
-            for i in 0..CHUNK_VOLUME {
-                blocks[i] = Block::new(BlockType::Stone)
-            }
+            blocks.fill(Block::new(BlockType::Stone));
             return;
         }
 
-        let mut warp_gen = FastNoiseLite::with_seed(seed as i32);
-        warp_gen.set_frequency(Some(WARP_SCALE_XY));
-        warp_gen.set_domain_warp_amp(Some(600.0));
-
-        let mut noise_gen = FastNoiseLite::with_seed(seed as i32);
-        noise_gen.set_fractal_type(Some(FractalType::FBm));
-        noise_gen.set_fractal_octaves(Some(7));
-        noise_gen.set_frequency(Some(FRACTAL_SCALE_XY));
+        let warp_gen = Self::warp_noise(seed);
+        let noise_gen = Self::height_noise(seed);
 
         let mut surface_get = FastNoiseLite::with_seed(seed as i32);
         surface_get.set_noise_type(Some(NoiseType::OpenSimplex2));
@@ -0,0 +1,96 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+
+// same as rayon pool in main.rs, terrain gen grows the stack with fat LTO
+pub const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Persistent pool of threads running one job function. Jobs go in through one channel,
+/// results come back through another in completion order, so the main thread never waits
+/// on the work itself. A job whose function returns `None` sends nothing back.
+pub struct WorkerPool<Job, Output> {
+    job_sender: Option<Sender<Job>>,
+    // behind a mutex only so `World` stays `Sync`
+    result_receiver: Mutex<Receiver<Output>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl<Job: Send + 'static, Output: Send + 'static> WorkerPool<Job, Output> {
+    /// Threads are named `{name}-{i}`.
+    pub fn spawn(name: &str, worker_count: usize, work: fn(Job) -> Option<Output>) -> Self {
+        let (job_sender, job_receiver) = mpsc::channel::<Job>();
+        let (result_sender, result_receiver) = mpsc::channel::<Output>();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let handles = (0..worker_count.max(1))
+            .map(|i| {
+                let jobs = Arc::clone(&job_receiver);
+                let results = result_sender.clone();
+                thread::Builder::new()
+                    .name(format!("{name}-{i}"))
+                    .stack_size(WORKER_STACK_SIZE)
+                    .spawn(move || Self::worker_loop(jobs, results, work))
+                    .unwrap_or_else(|e| panic!("Failed to spawn {name} worker: {e}"))
+            })
+            .collect();
+
+        Self {
+            job_sender: Some(job_sender),
+            result_receiver: Mutex::new(result_receiver),
+            handles,
+        }
+    }
+
+    fn worker_loop(
+        jobs: Arc<Mutex<Receiver<Job>>>,
+        results: Sender<Output>,
+        work: fn(Job) -> Option<Output>,
+    ) {
+        loop {
+            // lock is released as soon as a job is taken
+            let job = match jobs.lock().unwrap().recv() {
+                Ok(job) => job,
+                Err(_) => return, // sender dropped, pool is shutting down
+            };
+            let Some(output) = work(job) else {
+                continue;
+            };
+            if results.send(output).is_err() {
+                return;
+            }
+        }
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn submit(&self, job: Job) {
+        if let Some(sender) = &self.job_sender {
+            sender.send(job).expect("Workers are gone");
+        }
+    }
+
+    /// Non-blocking, returns `None` when no finished result is waiting.
+    pub fn try_recv(&self) -> Option<Output> {
+        self.result_receiver.lock().unwrap().try_recv().ok()
+    }
+
+    /// Blocks until the next result is finished.
+    pub fn recv(&self) -> Option<Output> {
+        self.result_receiver.lock().unwrap().recv().ok()
+    }
+}
+
+impl<Job, Output> Drop for WorkerPool<Job, Output> {
+    fn drop(&mut self) {
+        self.job_sender.take();
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
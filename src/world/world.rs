@@ -1,73 +1,226 @@
 use crate::core::{
     block::Block,
     chunk::{CHUNK_SIZE, Chunk},
-    meshing::{Mesh, Vertex},
-    render::renderer::Renderer,
+    chunk_state::ChunkState,
+    meshing::{ChunkSnapshot, Mesh},
+    render::frustum_culling::Frustum,
+};
+use crate::world::{
+    chunk_workers::{ChunkWorkers, LoadToken},
+    far_terrain::FarTerrain,
+    load_scheduler::LoadScheduler,
+    load_tickets::LoadTickets,
+    mesh_sink::MeshSink,
+    mesh_workers::{MeshJob, MeshWorkers, MeshedChunk},
+    observer::Observer,
+    settings::WorldSettings,
+    surface_map::{ChunkFill, SurfaceMap},
 };
 use cgmath::Vector3;
 use hashbrown::HashMap;
-use rayon::prelude::*;
-use std::{
-    collections::{HashSet, VecDeque},
-    time::Duration,
-};
+use std::collections::HashSet;
+
+// upper bound of chunks handed to the mesh workers but not swapped in yet
+const MAX_IN_FLIGHT_MESHES: usize = 64;
 
 pub struct World {
     pub chunks: HashMap<(i64, i64, i64), Chunk>,
     pub meshes: HashMap<(i64, i64, i64), Mesh>,
     pub seed: u32,
     pub dirty_chunks: HashSet<(i64, i64, i64)>,
-    pub need_to_load: VecDeque<(i64, i64, i64)>,
+    pub need_to_load: LoadScheduler,
+    pub in_flight: HashMap<(i64, i64, i64), LoadToken>,
+    pub workers: ChunkWorkers,
+    pub next_load_generation: u64,
+    /// chunks handed to the mesh workers, with the generation of their latest job
+    pub meshing: HashMap<(i64, i64, i64), u64>,
+    pub mesh_workers: MeshWorkers,
+    pub next_mesh_generation: u64,
+    pub settings: WorldSettings,
+    pub tickets: LoadTickets,
+    /// of the last `loader_update`
+    pub observers: Vec<Observer>,
+    /// observer chunks the chunk LODs were last picked for
+    pub lod_centers: Vec<Vector3<i64>>,
+    pub visibility_frame: u64,
+    /// evicted by the memory budget, queued again once visible
+    pub evicted: HashSet<(i64, i64, i64)>,
+    /// visible chunks alone outgrew the budget on the last check, only changes are logged
+    pub over_memory_budget: bool,
+    pub far_terrain: FarTerrain,
 }
 
 impl World {
     pub fn new(seed: u32) -> Self {
+        Self::with_settings(seed, WorldSettings::default())
+    }
+
+    pub fn with_settings(seed: u32, settings: WorldSettings) -> Self {
+        let settings = settings.sanitized();
         Self {
             chunks: HashMap::new(),
             meshes: HashMap::new(),
             seed,
             dirty_chunks: HashSet::new(),
-            need_to_load: VecDeque::new(),
+            need_to_load: LoadScheduler::new().with_surface(SurfaceMap::new(seed)),
+            in_flight: HashMap::new(),
+            workers: ChunkWorkers::default(),
+            next_load_generation: 0,
+            meshing: HashMap::new(),
+            mesh_workers: MeshWorkers::default(),
+            next_mesh_generation: 0,
+            settings,
+            tickets: LoadTickets::default(),
+            observers: Vec::new(),
+            lod_centers: Vec::new(),
+            visibility_frame: 0,
+            evicted: HashSet::new(),
+            over_memory_budget: false,
+            far_terrain: FarTerrain::new(seed),
+        }
+    }
+
+    /// One simulation step: loads around `observers`, picks chunk LODs, applies the memory
+    /// budget and publishes chunk mesh and far terrain changes to `sink`. Without a `frustum`
+    /// no chunk counts as visible.
+    pub fn update(
+        &mut self,
+        observers: &[Observer],
+        frustum: Option<&Frustum>,
+        sink: &mut impl MeshSink,
+    ) {
+        self.loader_update(observers);
+        self.update_lods();
+        self.update_visibility(frustum);
+        self.enforce_memory_budget();
+        self.finish_unloading(sink);
+        self.update_meshes(sink);
+        self.update_far_terrain(sink);
+    }
+
+    /// Swaps in meshes finished by the workers and hands dirty chunks to them.
+    /// Never blocks, a chunk keeps its previous mesh until the new one arrives.
+    fn update_meshes(&mut self, sink: &mut impl MeshSink) {
+        while let Some(meshed) = self.mesh_workers.try_recv() {
+            self.integrate_meshed(meshed, sink);
         }
+        self.dispatch_meshing();
     }
 
-    pub fn update(&mut self, has_time: Duration, renderer: &mut Renderer) {
-        self.loader_update(has_time, &renderer.camera);
-        renderer.cleanup_unused_meshes(&self.chunks);
-        self.update_meshes(renderer);
-    }
-
-    fn update_meshes(&mut self, renderer: &mut Renderer) {
-        let dirty_chunks = std::mem::take(&mut self.dirty_chunks);
-        let mesh_updates: Vec<((i64, i64, i64), (Vec<Vertex>, Vec<u32>))> = dirty_chunks
-            .par_iter()
-            .filter_map(|key| {
-                let chunk = self.chunks.get(key)?;
-                let mesh_data = chunk.generate_mesh(self);
-                Some((*key, mesh_data))
-            })
-            .collect();
-        for (key, (vertices, indices)) in mesh_updates {
-            if let Some(mesh) = self.meshes.get_mut(&key) {
-                mesh.update(vertices, indices);
-            } else {
-                let mesh = Mesh::new(vertices, indices);
-                self.meshes.insert(key, mesh);
+    /// Blocks until every dirty chunk is meshed and published to `sink`.
+    /// Meant for benches and tests, not for the frame loop.
+    pub fn finish_meshing(&mut self, sink: &mut impl MeshSink) {
+        loop {
+            self.dispatch_meshing();
+            if self.meshing.is_empty() {
+                break;
             }
-            renderer.on_mesh_updated(key);
-            if let Some(chunk) = self.chunks.get_mut(&key) {
-                chunk.is_dirty = false;
-                self.dirty_chunks.remove(&key);
+            match self.mesh_workers.recv() {
+                Some(meshed) => self.integrate_meshed(meshed, sink),
+                None => break,
             }
         }
     }
 
+    fn dispatch_meshing(&mut self) {
+        let mut dirty_chunks = std::mem::take(&mut self.dirty_chunks);
+        dirty_chunks.retain(|key| {
+            if self.meshing.len() >= MAX_IN_FLIGHT_MESHES {
+                return true;
+            }
+            let Some(chunk) = self.chunks.get(key) else {
+                return false;
+            };
+            // a chunk edited while meshing is sent again, the older result gets dropped
+            let remeshing = chunk.state() == ChunkState::Meshing;
+            if !remeshing && !chunk.is_meshable() {
+                return false;
+            }
+            let snapshot = ChunkSnapshot::capture(chunk, self);
+            if !remeshing {
+                let chunk = self.chunks.get_mut(key).unwrap();
+                Self::log_transition(chunk, ChunkState::Meshing);
+            }
+            let generation = self.next_mesh_generation;
+            self.next_mesh_generation += 1;
+            self.meshing.insert(*key, generation);
+            self.mesh_workers.submit(MeshJob {
+                key: *key,
+                generation,
+                snapshot,
+                mesher: self.settings.mesher,
+            });
+            false
+        });
+        self.dirty_chunks.extend(dirty_chunks);
+    }
+
+    fn integrate_meshed(&mut self, meshed: MeshedChunk, sink: &mut impl MeshSink) {
+        let key = meshed.key;
+        // unloaded or superseded by a newer snapshot of the same chunk
+        match self.meshing.get(&key) {
+            Some(&generation) if generation == meshed.generation => {
+                self.meshing.remove(&key);
+            }
+            _ => return,
+        }
+        let Some(chunk) = self.chunks.get_mut(&key) else {
+            return;
+        };
+        if chunk.state() != ChunkState::Meshing {
+            return;
+        }
+        Self::log_transition(chunk, ChunkState::Ready);
+        if let Some(mesh) = self.meshes.get_mut(&key) {
+            mesh.update(meshed.vertices);
+            mesh.axis_aligned = meshed.axis_aligned;
+        } else {
+            let mut mesh = Mesh::new(meshed.vertices);
+            mesh.axis_aligned = meshed.axis_aligned;
+            self.meshes.insert(key, mesh);
+        }
+        sink.mesh_updated(key);
+    }
+
+    /// Walks a freshly generated chunk up to `Lit` and queues it and its neighbours for meshing.
+    pub fn insert_generated(&mut self, key: (i64, i64, i64), mut chunk: Chunk) {
+        // no decoration or lighting passes yet, both stages complete immediately
+        Self::log_transition(&mut chunk, ChunkState::Decorated);
+        Self::log_transition(&mut chunk, ChunkState::Lit);
+        chunk.lod = self.lod_for(&key);
+        self.chunks.insert(key, chunk);
+        self.dirty_chunks.insert(key);
+        self.mark_neighbors_dirty(key);
+    }
+
+    fn log_transition(chunk: &mut Chunk, next: ChunkState) -> bool {
+        match chunk.transition(next) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("chunk {:?}: {}", chunk._pos, e);
+                false
+            }
+        }
+    }
+
+    /// Number of chunk positions per lifecycle state, including queued and generating ones.
+    pub fn state_counts(&self) -> HashMap<ChunkState, usize> {
+        let mut counts: HashMap<ChunkState, usize> =
+            ChunkState::ALL.iter().map(|&state| (state, 0)).collect();
+        counts.insert(ChunkState::Queued, self.need_to_load.len());
+        counts.insert(ChunkState::Generating, self.in_flight.len());
+        for chunk in self.chunks.values() {
+            *counts.entry(chunk.state()).or_default() += 1;
+        }
+        counts
+    }
+
     pub fn load_chunk(&mut self, x: i64, y: i64, z: i64) {
         let key = (x, y, z);
         if !self.chunks.contains_key(&key) {
             let world_pos = Vector3 { x, y, z };
             let chunk = Chunk::terrain_gen(world_pos, self.seed);
-            self.chunks.insert(key, chunk);
+            self.insert_generated(key, chunk);
         }
     }
 
@@ -80,18 +233,39 @@ impl World {
         self.chunks.get(&chunk_idx)
     }
 
+    /// Content of a chunk that is not loaded, when the surface estimate rules it out.
+    pub fn known_fill(&self, chunk_key: &(i64, i64, i64)) -> Option<ChunkFill> {
+        self.need_to_load.surface()?.fill(chunk_key)
+    }
+
     pub fn get_block(&self, world_pos: Vector3<i64>) -> Option<Block> {
         let chunk = self.get_chunk(&world_pos);
         chunk.map(|chunk| chunk.get_from_world_pos(world_pos))
     }
 
+    /// Marks the chunk `Unloading`, it is removed by the next `finish_unloading`.
     pub fn drop_chunk(&mut self, world_pos: Vector3<i64>) {
         let key = (world_pos.x, world_pos.y, world_pos.z);
-        self.chunks.remove(&key);
-        self.meshes.remove(&key);
+        if let Some(chunk) = self.chunks.get_mut(&key) {
+            Self::log_transition(chunk, ChunkState::Unloading);
+        }
         self.dirty_chunks.remove(&key);
     }
 
+    /// Removes every chunk in `Unloading` together with its mesh.
+    pub fn finish_unloading(&mut self, sink: &mut impl MeshSink) {
+        let (meshes, meshing) = (&mut self.meshes, &mut self.meshing);
+        self.chunks.retain(|key, chunk| {
+            let keep = chunk.state() != ChunkState::Unloading;
+            if !keep {
+                meshes.remove(key);
+                meshing.remove(key);
+                sink.mesh_removed(*key);
+            }
+            keep
+        });
+    }
+
     pub fn is_face_exposed(&self, pos: Vector3<f32>, dir: Vector3<f32>) -> bool {
         let neighbor = Vector3::new(
             (pos.x + dir.x) as i64,
@@ -99,9 +273,7 @@ impl World {
             (pos.z + dir.z) as i64,
         );
         let chunk = self.get_chunk(&neighbor);
-        if let Some(chunk) = chunk
-            && chunk.is_rendered
-        {
+        if let Some(chunk) = chunk {
             let block = chunk.get_from_world_pos(neighbor);
             block.is_transpose()
         } else {
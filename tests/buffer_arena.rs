@@ -0,0 +1,172 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rustcraft::core::{
+    meshing::ChunkOrigin,
+    render::{
+        buffer_arena::{ArenaAllocation, ArenaAllocator},
+        chunk_draws::DrawList,
+        upload_queue::UploadQueue,
+    },
+};
+
+#[test]
+fn allocations_are_first_fit_and_reuse_freed_space() {
+    let mut arena = ArenaAllocator::new(100);
+    let a = arena.allocate(30).unwrap();
+    let b = arena.allocate(30).unwrap();
+    let c = arena.allocate(30).unwrap();
+    assert_eq!([a.offset, b.offset, c.offset], [0, 30, 60]);
+    assert_eq!(arena.used_units(), 90);
+    assert!(arena.allocate(20).is_none(), "only 10 units left");
+
+    arena.free(b);
+    assert_eq!(arena.free_blocks().count(), 2);
+    // the hole in the middle comes first
+    assert_eq!(arena.allocate(20).unwrap().offset, 30);
+    assert_eq!(arena.allocate(10).unwrap().offset, 50);
+    assert_eq!(arena.allocate(10).unwrap().offset, 90);
+    assert_eq!(arena.free_units(), 0);
+}
+
+#[test]
+fn freeing_merges_neighbouring_blocks() {
+    let mut arena = ArenaAllocator::new(90);
+    let blocks: Vec<_> = (0..3).map(|_| arena.allocate(30).unwrap()).collect();
+    arena.free(blocks[0]);
+    arena.free(blocks[2]);
+    assert_eq!(arena.largest_free_block(), 30);
+    // the middle block bridges both free neighbours into one
+    arena.free(blocks[1]);
+    assert_eq!(
+        arena.free_blocks().collect::<Vec<_>>(),
+        [ArenaAllocation {
+            offset: 0,
+            size: 90
+        }]
+    );
+    assert_eq!(arena.allocate(90).unwrap().offset, 0);
+}
+
+#[test]
+fn growing_extends_the_free_tail() {
+    let mut arena = ArenaAllocator::new(64);
+    let a = arena.allocate(48).unwrap();
+    assert!(arena.allocate(32).is_none());
+    arena.grow(128);
+    assert_eq!(arena.capacity(), 128);
+    assert_eq!(
+        arena.largest_free_block(),
+        80,
+        "old tail and new space merged"
+    );
+    assert_eq!(arena.allocate(32).unwrap().offset, 48);
+    arena.free(a);
+    assert_eq!(arena.used_units(), 32);
+}
+
+#[test]
+fn shrinking_frees_the_tail() {
+    let mut arena = ArenaAllocator::new(100);
+    let a = arena.allocate(60).unwrap();
+    let a = arena.shrink(a, 20);
+    assert_eq!(
+        a,
+        ArenaAllocation {
+            offset: 0,
+            size: 20
+        }
+    );
+    assert_eq!(arena.used_units(), 20);
+    // the freed tail merged with the untouched rest
+    assert_eq!(arena.largest_free_block(), 80);
+    assert_eq!(arena.allocate(80).unwrap().offset, 20);
+}
+
+#[test]
+fn random_churn_never_overlaps() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut arena = ArenaAllocator::new(10_000);
+    let mut live: Vec<ArenaAllocation> = Vec::new();
+    for _ in 0..5_000 {
+        if !live.is_empty() && rng.gen_bool(0.45) {
+            let allocation = live.swap_remove(rng.gen_range(0..live.len()));
+            arena.free(allocation);
+        } else if let Some(allocation) = arena.allocate(rng.gen_range(1..400)) {
+            live.push(allocation);
+        }
+
+        let used: u64 = live.iter().map(|allocation| allocation.size).sum();
+        assert_eq!(arena.used_units(), used);
+        live.sort_by_key(|allocation| allocation.offset);
+        for pair in live.windows(2) {
+            assert!(pair[0].offset + pair[0].size <= pair[1].offset);
+        }
+        // free blocks are always merged, never two in a row
+        let free: Vec<_> = arena.free_blocks().collect();
+        for pair in free.windows(2) {
+            assert!(pair[0].offset + pair[0].size < pair[1].offset);
+        }
+    }
+    for allocation in live.drain(..) {
+        arena.free(allocation);
+    }
+    assert_eq!(arena.largest_free_block(), 10_000);
+}
+
+#[test]
+fn draw_list_merges_visible_ranges_per_chunk() {
+    let ranges = [0..60, 60..60, 60..120, 120..180, 180..600, 600..660];
+    let mut list = DrawList::default();
+    // +X, +Y and +Z face the camera, the empty -X range doesn't split +X from +Y
+    list.push_chunk(
+        ChunkOrigin::new([0, 0, 0]),
+        100,
+        &ranges,
+        [true, false, true, false, true, false],
+    );
+    assert_eq!(list.instances.len(), 1);
+    let draws: Vec<_> = list
+        .draws
+        .iter()
+        .map(|draw| (draw.first_index, draw.index_count, draw.base_vertex))
+        .collect();
+    assert_eq!(draws, [(0, 120, 100), (180, 420, 100)]);
+
+    // nothing facing the camera adds neither a draw nor an instance
+    list.push_chunk(ChunkOrigin::new([32, 0, 0]), 0, &ranges, [false; 6]);
+    assert_eq!(list.instances.len(), 1);
+
+    list.push_chunk(ChunkOrigin::new([0, 32, 0]), 900, &ranges, [true; 6]);
+    assert_eq!(list.instances.len(), 2);
+    let last = list.draws.last().unwrap();
+    assert_eq!((last.first_index, last.index_count), (0, 660));
+    assert_eq!(
+        last.first_instance, 1,
+        "draws point at their chunk's origin"
+    );
+    assert_eq!(list.index_count(), 120 + 420 + 660);
+
+    list.clear();
+    assert!(list.draws.is_empty() && list.instances.is_empty());
+}
+
+#[test]
+fn upload_queue_takes_the_most_urgent_within_budget() {
+    let mut queue = UploadQueue::default();
+    for x in 0..5 {
+        queue.push((x, 0, 0));
+    }
+    queue.push((2, 0, 0));
+    assert_eq!(queue.len(), 5, "a chunk is queued once");
+
+    // chunk 3 has no mesh anymore and leaves without costing anything
+    let bytes = |key: &(i64, i64, i64)| (key.0 != 3).then_some(100);
+    let frame = queue.take_frame(250, |key| -key.0, bytes);
+    assert_eq!(frame, [(4, 0, 0), (2, 0, 0)]);
+    assert_eq!(queue.len(), 2);
+
+    // a mesh over the budget still goes through, alone
+    let frame = queue.take_frame(10, |key| key.0, bytes);
+    assert_eq!(frame, [(0, 0, 0)]);
+    queue.remove(&(1, 0, 0));
+    assert!(queue.is_empty());
+}
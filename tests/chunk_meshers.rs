@@ -0,0 +1,229 @@
+use cgmath::{InnerSpace, Vector3};
+use rand::{SeedableRng, rngs::StdRng};
+use rustcraft::{
+    core::{
+        block::Block,
+        chunk::Chunk,
+        meshing::{
+            ChunkMesher, ChunkSnapshot, GreedyMesher, MAX_CHUNK_QUADS, Mesh, MesherKind,
+            NaiveMesher, SmoothMesher, Vertex, quad_indices,
+        },
+        render::frustum_culling::facing_directions,
+    },
+    world::{World, mesh_sink::NullMeshSink},
+};
+
+mod common;
+use common::{position, random_chunk, terrain_world};
+
+const MESHERS: [&dyn ChunkMesher; 3] = [&NaiveMesher, &GreedyMesher, &SmoothMesher];
+
+fn area_by_direction(vertices: &[Vertex]) -> [f32; 6] {
+    let mut area = [0.0; 6];
+    for quad in vertices.chunks(4) {
+        let [a, b, _, d] = [0, 1, 2, 3].map(|i| position(&quad[i]));
+        area[quad[0].normal() as usize] += (b - a).cross(d - a).magnitude();
+    }
+    area
+}
+
+fn assert_well_formed(mesher: &dyn ChunkMesher, snapshot: &ChunkSnapshot) {
+    let vertices = mesher.mesh(snapshot);
+    assert_eq!(vertices.len() % 4, 0);
+    assert!(vertices.len() / 4 <= MAX_CHUNK_QUADS);
+    for vertex in &vertices {
+        // a block past either side of the chunk at most
+        assert!(vertex.local_pos().iter().all(|c| (-1.0..=32.0).contains(c)));
+    }
+}
+
+fn assert_same_area(snapshot: &ChunkSnapshot, label: &str) {
+    let naive = area_by_direction(&NaiveMesher.mesh(snapshot));
+    let greedy = area_by_direction(&GreedyMesher.mesh(snapshot));
+    assert_eq!(naive, greedy, "face area per direction of {label}");
+}
+
+#[test]
+fn every_mesher_is_well_formed_on_terrain() {
+    let world = terrain_world();
+    for chunk in world.chunks.values() {
+        let snapshot = ChunkSnapshot::capture(chunk, &world);
+        for mesher in MESHERS {
+            assert_well_formed(mesher, &snapshot);
+        }
+    }
+}
+
+#[test]
+fn greedy_covers_the_naive_area_on_terrain() {
+    let world = terrain_world();
+    for chunk in world.chunks.values() {
+        let snapshot = ChunkSnapshot::capture(chunk, &world);
+        assert_same_area(&snapshot, &format!("chunk {:?}", chunk._pos));
+    }
+}
+
+#[test]
+fn greedy_covers_the_naive_area_on_random_chunks() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for density in [0.05, 0.3, 0.6, 0.95] {
+        let mut world = World::new(1);
+        for key in [(0, 0, 0), (-1, 0, 0), (0, 1, 0), (0, 0, -1)] {
+            let pos = Vector3::new(key.0, key.1, key.2);
+            world
+                .chunks
+                .insert(key, random_chunk(&mut rng, pos, density));
+        }
+        let snapshot = ChunkSnapshot::capture(&world.chunks[&(0, 0, 0)], &world);
+        assert_same_area(&snapshot, &format!("density {density}"));
+        for mesher in MESHERS {
+            assert_well_formed(mesher, &snapshot);
+        }
+    }
+}
+
+/// Staircase rising one block per column along x, in chunk (0, 0, 0).
+fn staircase_snapshot() -> ChunkSnapshot {
+    let mut world = World::new(1);
+    world.load_chunk(0, 0, 0);
+    let chunk = world.chunks.get_mut(&(0, 0, 0)).unwrap();
+    chunk.blocks.fill(Block::air());
+    for x in 0..16 {
+        for y in 0..32 {
+            for z in 0..=x {
+                chunk.blocks[Chunk::index(x, y, z)] = Block::from_id(1);
+            }
+        }
+    }
+    ChunkSnapshot::capture(&world.chunks[&(0, 0, 0)], &world)
+}
+
+#[test]
+fn smooth_mesher_cuts_the_corners_of_steps() {
+    let snapshot = staircase_snapshot();
+    let blocky = NaiveMesher.mesh(&snapshot);
+    let smooth = SmoothMesher.mesh(&snapshot);
+    assert_eq!(smooth.len(), blocky.len(), "one quad per visible face");
+    let on_grid = |c: f32| (c + 0.5).fract() == 0.0;
+    assert!(
+        blocky
+            .iter()
+            .all(|v| v.local_pos().into_iter().all(on_grid))
+    );
+    assert!(
+        smooth
+            .iter()
+            .any(|v| !v.local_pos().into_iter().all(on_grid)),
+        "stair corners stay sharp"
+    );
+    // quads keep facing out of the solid side
+    for quad in smooth.chunks(4) {
+        let [a, b, c] = [0, 1, 2].map(|i| position(&quad[i]));
+        let normal = (b - a).cross(c - a);
+        let facing = Vector3::from([
+            [1.0, -1.0, 0.0, 0.0, 0.0, 0.0][quad[0].normal() as usize],
+            [0.0, 0.0, 1.0, -1.0, 0.0, 0.0][quad[0].normal() as usize],
+            [0.0, 0.0, 0.0, 0.0, 1.0, -1.0][quad[0].normal() as usize],
+        ]);
+        assert!(normal.magnitude2() == 0.0 || normal.dot(facing) > 0.0);
+    }
+}
+
+#[test]
+fn worlds_mesh_with_their_selected_mesher() {
+    let mut world = terrain_world();
+    world.finish_meshing(&mut NullMeshSink);
+    let greedy: usize = world.meshes.values().map(|mesh| mesh.vertices.len()).sum();
+
+    let settings = world.settings.with_mesher(MesherKind::Naive);
+    world.set_settings(settings);
+    assert!(!world.dirty_chunks.is_empty(), "switching meshers remeshes");
+    world.finish_meshing(&mut NullMeshSink);
+    let naive: usize = world.meshes.values().map(|mesh| mesh.vertices.len()).sum();
+    assert!(naive > greedy);
+}
+
+#[test]
+fn meshes_split_into_one_index_range_per_direction() {
+    let world = terrain_world();
+    for chunk in world.chunks.values() {
+        let snapshot = ChunkSnapshot::capture(chunk, &world);
+        for mesher in MESHERS {
+            let mesh = Mesh::new(mesher.mesh(&snapshot));
+            let indices = quad_indices(mesh.vertices.len() / 4);
+            let mut end = 0;
+            for (direction, range) in mesh.direction_ranges.iter().enumerate() {
+                assert_eq!(range.start, end);
+                end = range.end;
+                for &index in &indices[range.start as usize..range.end as usize] {
+                    assert_eq!(mesh.vertices[index as usize].normal(), direction as u32);
+                }
+            }
+            assert_eq!(end, mesh.index_count);
+        }
+    }
+}
+
+#[test]
+fn direction_groups_facing_away_are_culled() {
+    let key = (0, 0, 0);
+    // inside the chunk every face can be seen
+    assert_eq!(
+        facing_directions(Vector3::new(16.0, 16.0, 16.0), &key, true),
+        [true; 6]
+    );
+    // past +X only the +X faces of the chunk face the camera along that axis
+    let beyond_x = facing_directions(Vector3::new(100.0, 16.0, 16.0), &key, true);
+    assert_eq!(beyond_x[..2], [true, false]);
+    let below = facing_directions(Vector3::new(16.0, 16.0, -50.0), &key, true);
+    assert_eq!(below[4..], [false, true]);
+
+    // looking at the terrain from above and aside, about half the triangles are skipped
+    let world = terrain_world();
+    let eye = Vector3::new(-80.0, 70.0, 60.0);
+    let (mut total, mut drawn) = (0, 0);
+    for chunk in world.chunks.values() {
+        let mesh = Mesh::new(chunk.generate_mesh(&world));
+        let key = (chunk._pos.x, chunk._pos.y, chunk._pos.z);
+        total += mesh.index_count;
+        for (range, facing) in
+            mesh.direction_ranges
+                .iter()
+                .zip(facing_directions(eye, &key, mesh.axis_aligned))
+        {
+            if facing {
+                drawn += range.len() as u32;
+            }
+        }
+    }
+    assert!(total > 0);
+    assert!(drawn * 10 < total * 7, "drew {drawn} of {total} indices");
+}
+
+#[test]
+fn tilted_smooth_quads_are_never_culled() {
+    let key = (0, 0, 0);
+    let snapshot = staircase_snapshot();
+    // above the stairs and past the chunk's +X side, the smoothed -X risers slope towards it
+    let eye = Vector3::new(40.0, 16.0, 200.0);
+    for mesher in [&SmoothMesher as &dyn ChunkMesher, &NaiveMesher] {
+        let vertices = mesher.mesh(&snapshot);
+        let facing = facing_directions(eye, &key, mesher.axis_aligned());
+        let mut front_facing = [false; 6];
+        for quad in vertices.chunks(4) {
+            let direction = quad[0].normal() as usize;
+            for triangle in [[0, 1, 2], [2, 3, 0]] {
+                let [a, b, c] = triangle.map(|i| position(&quad[i]));
+                if (b - a).cross(c - a).dot(eye - a) > 0.0 {
+                    front_facing[direction] = true;
+                    assert!(
+                        facing[direction],
+                        "front facing triangle in culled group {direction}"
+                    );
+                }
+            }
+        }
+        // the smoothed risers are what a bounds-only cull would have dropped
+        assert_eq!(front_facing[1], !mesher.axis_aligned());
+    }
+}
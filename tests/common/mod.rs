@@ -0,0 +1,64 @@
+//! Fixtures shared by the integration tests, each test crate uses a different subset.
+#![allow(dead_code)]
+
+use cgmath::Vector3;
+use rand::{Rng, rngs::StdRng};
+use rustcraft::{
+    core::{
+        block::Block,
+        chunk::{CHUNK_VOLUME, Chunk},
+        meshing::Vertex,
+    },
+    world::{World, mesh_sink::MeshSink, observer::Observer, settings::WorldSettings},
+};
+
+/// At `x` blocks along the x axis, looking towards +x.
+pub fn observer_at(x: f32, settings: &WorldSettings) -> Observer {
+    Observer::new(
+        Vector3::new(x, 0.0, 0.0),
+        Vector3::new(1.0, 0.0, 0.0),
+        settings,
+    )
+}
+
+/// Everything around `observer` generated and meshed.
+pub fn loaded_world(
+    settings: WorldSettings,
+    observer: Observer,
+    sink: &mut impl MeshSink,
+) -> World {
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, sink);
+    world.finish_loading();
+    world.update(&[observer], None, sink);
+    world.finish_meshing(sink);
+    world
+}
+
+/// The 3×3×3 chunks around the origin, generated but not meshed.
+pub fn terrain_world() -> World {
+    let mut world = World::new(6969);
+    for x in -1..=1 {
+        for y in -1..=1 {
+            for z in -1..=1 {
+                world.need_to_load.push((x, y, z));
+            }
+        }
+    }
+    world.finish_loading();
+    world
+}
+
+pub fn random_chunk(rng: &mut StdRng, pos: Vector3<i64>, density: f64) -> Chunk {
+    let mut chunk = Chunk::new_empty(pos);
+    for i in 0..CHUNK_VOLUME {
+        if rng.gen_bool(density) {
+            chunk.blocks[i] = Block::from_id(rng.gen_range(1..=3));
+        }
+    }
+    chunk
+}
+
+pub fn position(vertex: &Vertex) -> Vector3<f32> {
+    vertex.local_pos().into()
+}
@@ -0,0 +1,114 @@
+use cgmath::Vector3;
+use rustcraft::{
+    core::meshing::{FAR_TILE_CHUNKS, FarTile},
+    world::{World, far_terrain::FarTerrain, mesh_sink::MeshSink, settings::WorldSettings},
+};
+
+mod common;
+use common::observer_at;
+
+#[derive(Default)]
+struct TileSink {
+    updated: Vec<(i64, i64)>,
+    removed: Vec<(i64, i64)>,
+}
+
+impl MeshSink for TileSink {
+    fn mesh_updated(&mut self, _key: (i64, i64, i64)) {}
+
+    fn mesh_removed(&mut self, _key: (i64, i64, i64)) {}
+
+    fn far_tile_updated(&mut self, key: (i64, i64)) {
+        self.updated.push(key);
+    }
+
+    fn far_tile_removed(&mut self, key: (i64, i64)) {
+        self.removed.push(key);
+    }
+}
+
+#[test]
+fn tiles_fill_the_far_distance_nearest_first() {
+    let settings = WorldSettings::default()
+        .with_load_distance(1)
+        .with_far_distance(2 * FAR_TILE_CHUNKS);
+    let mut world = World::with_settings(7, settings);
+    let mut sink = TileSink::default();
+    for _ in 0..10 {
+        world.update(&[observer_at(0.0, &settings)], None, &mut sink);
+    }
+
+    assert_eq!(world.far_terrain.tiles.len(), 25);
+    assert_eq!(sink.updated.len(), 25);
+    assert_eq!(sink.updated[0], (0, 0));
+    let ring = |key: &(i64, i64)| key.0.abs().max(key.1.abs());
+    assert!(
+        sink.updated
+            .windows(2)
+            .all(|pair| ring(&pair[0]) <= ring(&pair[1]))
+    );
+    for (key, tile) in &world.far_terrain.tiles {
+        assert_eq!(tile.grid, FarTerrain::grid_for(ring(key)));
+    }
+
+    // far enough that no tile is shared
+    let far_away = 10.0 * (FAR_TILE_CHUNKS * 32) as f32;
+    world.update(&[observer_at(far_away, &settings)], None, &mut sink);
+    assert_eq!(sink.removed.len(), 25);
+}
+
+#[test]
+fn hole_follows_meshed_columns() {
+    let settings = WorldSettings::default()
+        .with_load_distance(1)
+        .with_far_distance(FAR_TILE_CHUNKS);
+    let observer = observer_at(0.0, &settings);
+    let mut world = World::with_settings(7, settings);
+    let mut sink = TileSink::default();
+    world.update(&[observer], None, &mut sink);
+    assert_eq!(world.far_terrain.hole(), None);
+
+    world.finish_loading();
+    world.update(&[observer], None, &mut sink);
+    world.finish_meshing(&mut sink);
+    world.update(&[observer], None, &mut sink);
+    // every column of the load area, blocks are centered on integers
+    assert_eq!(
+        world.far_terrain.hole(),
+        Some(([-32.5, -32.5], [63.5, 63.5]))
+    );
+
+    // a corner column losing a surface chunk shrinks the hole back to the center one
+    let window = world.need_to_load.surface().unwrap().window(1, 1).unwrap();
+    world.drop_chunk(Vector3::new(1, 1, window.min_z));
+    world.update(&[observer], None, &mut sink);
+    assert_eq!(world.far_terrain.hole(), Some(([-0.5, -0.5], [31.5, 31.5])));
+}
+
+#[test]
+fn tile_surface_faces_up_and_skirts_hang_below() {
+    let grid = 16;
+    let tile = FarTile::build(7, (1, -1), grid);
+    let surface_indices = 6 * grid * grid;
+    let surface_vertices = (grid + 1) * (grid + 1);
+    for triangle in tile.indices[..surface_indices].chunks(3) {
+        let [a, b, c] =
+            [0, 1, 2].map(|i| Vector3::from(tile.vertices[triangle[i] as usize].position));
+        assert!((b - a).cross(c - a).z > 0.0);
+    }
+    let (min, max) = FarTile::bounds(tile.key);
+    for skirt in &tile.vertices[surface_vertices..] {
+        let [x, y, z] = skirt.position;
+        assert!(x == min[0] || x == max[0] || y == min[1] || y == max[1]);
+        let top = tile.vertices[..surface_vertices]
+            .iter()
+            .find(|vertex| vertex.position[0] == x && vertex.position[1] == y)
+            .unwrap();
+        assert!(z < top.position[2]);
+    }
+    assert!(
+        tile.indices
+            .iter()
+            .all(|&i| (i as usize) < tile.vertices.len())
+    );
+}
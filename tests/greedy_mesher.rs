@@ -0,0 +1,66 @@
+use cgmath::Vector3;
+use rand::{SeedableRng, rngs::StdRng};
+use rustcraft::{
+    core::{
+        block::Block,
+        chunk::Chunk,
+        meshing::{ChunkSnapshot, GreedyMesher, reference_mesher::ReferenceMesher},
+    },
+    world::World,
+};
+
+mod common;
+use common::random_chunk;
+
+fn assert_same_mesh(chunk: &Chunk, world: &World) {
+    let snapshot = ChunkSnapshot::capture(chunk, world);
+    let vertices = GreedyMesher::build_mesh(&snapshot);
+    let expected_vertices = ReferenceMesher::build_mesh(&snapshot);
+    assert_eq!(
+        vertices, expected_vertices,
+        "vertices of chunk {:?}",
+        chunk._pos
+    );
+}
+
+#[test]
+fn matches_reference_on_generated_terrain() {
+    let mut world = World::new(6969);
+    for x in -1..=1 {
+        for y in -1..=1 {
+            for z in -1..=1 {
+                world.need_to_load.push((x, y, z));
+            }
+        }
+    }
+    world.finish_loading();
+
+    for chunk in world.chunks.values() {
+        assert_same_mesh(chunk, &world);
+    }
+}
+
+#[test]
+fn matches_reference_on_random_chunks_and_borders() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for density in [0.05, 0.3, 0.6, 0.95] {
+        let mut world = World::new(1);
+        // neighbours on some sides only, missing ones fall back to the surface estimate
+        for key in [(0, 0, 0), (1, 0, 0), (0, -1, 0), (0, 0, 1)] {
+            let pos = Vector3::new(key.0, key.1, key.2);
+            world
+                .chunks
+                .insert(key, random_chunk(&mut rng, pos, density));
+        }
+        assert_same_mesh(&world.chunks[&(0, 0, 0)], &world);
+    }
+}
+
+#[test]
+fn matches_reference_on_full_and_empty_chunks() {
+    let world = World::new(1);
+    let mut full = Chunk::new_empty(Vector3::new(0, 0, 0));
+    full.blocks.fill(Block::from_id(1));
+    assert_same_mesh(&full, &world);
+    assert_same_mesh(&Chunk::new_empty(Vector3::new(0, 0, 0)), &world);
+}
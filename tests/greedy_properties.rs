@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3};
+use proptest::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rustcraft::{
+    core::{
+        block::Block,
+        chunk::{CHUNK_SIZE, Chunk},
+        meshing::{
+            ChunkMesher, ChunkSnapshot, GreedyMesher, MAX_CHUNK_QUADS, NaiveMesher, Vertex,
+            quad_indices,
+        },
+    },
+    world::{World, load_scheduler::LoadScheduler, surface_map::SurfaceMap},
+};
+
+mod common;
+use common::position;
+
+const FACE_NEIGHBOURS: [(i64, i64, i64); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// Axis-aligned box of one block type, clipped to the chunk.
+#[derive(Debug, Clone)]
+struct Cuboid {
+    min: (usize, usize, usize),
+    size: (usize, usize, usize),
+    id: u16,
+}
+
+/// Random chunk with neighbours on some sides, missing ones fall back to the surface estimate.
+#[derive(Debug, Clone)]
+struct Config {
+    cuboids: Vec<Cuboid>,
+    /// chance of a random block in every cell on top of the cuboids
+    noise: f64,
+    /// bit per entry of `FACE_NEIGHBOURS`
+    neighbours: u8,
+    neighbour_density: f64,
+    seed: u64,
+}
+
+fn cuboid() -> impl Strategy<Value = Cuboid> {
+    let corner = (0..CHUNK_SIZE, 0..CHUNK_SIZE, 0..CHUNK_SIZE);
+    let size = (1..=CHUNK_SIZE, 1..=CHUNK_SIZE, 1..=CHUNK_SIZE);
+    (corner, size, 1..=3u16).prop_map(|(min, size, id)| Cuboid { min, size, id })
+}
+
+fn config() -> impl Strategy<Value = Config> {
+    (
+        prop::collection::vec(cuboid(), 0..8),
+        prop_oneof![Just(0.0), 0.0..0.2, 0.4..1.0],
+        any::<u8>(),
+        prop_oneof![Just(0.0), Just(1.0), 0.0..1.0],
+        any::<u64>(),
+    )
+        .prop_map(
+            |(cuboids, noise, neighbours, neighbour_density, seed)| Config {
+                cuboids,
+                noise,
+                neighbours: neighbours & 0b11_1111,
+                neighbour_density,
+                seed,
+            },
+        )
+}
+
+fn build_world(config: &Config) -> World {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut world = World::new(1);
+    let mut chunk = Chunk::new_empty(Vector3::new(0, 0, 0));
+    for cuboid in &config.cuboids {
+        let (x0, y0, z0) = cuboid.min;
+        let (w, h, d) = cuboid.size;
+        for x in x0..(x0 + w).min(CHUNK_SIZE) {
+            for y in y0..(y0 + h).min(CHUNK_SIZE) {
+                for z in z0..(z0 + d).min(CHUNK_SIZE) {
+                    chunk.blocks[Chunk::index(x, y, z)] = Block::from_id(cuboid.id);
+                }
+            }
+        }
+    }
+    for block in chunk.blocks.iter_mut() {
+        if rng.gen_bool(config.noise) {
+            *block = Block::from_id(rng.gen_range(1..=3));
+        }
+    }
+    world.chunks.insert((0, 0, 0), chunk);
+
+    for (i, &key) in FACE_NEIGHBOURS.iter().enumerate() {
+        if config.neighbours & (1 << i) == 0 {
+            continue;
+        }
+        let mut neighbour = Chunk::new_empty(Vector3::new(key.0, key.1, key.2));
+        for block in neighbour.blocks.iter_mut() {
+            if rng.gen_bool(config.neighbour_density) {
+                *block = Block::from_id(rng.gen_range(1..=3));
+            }
+        }
+        world.chunks.insert(key, neighbour);
+    }
+    world
+}
+
+fn normal_vector(normal: u32) -> Vector3<f32> {
+    let axis = normal as usize / 2;
+    let sign = if normal.is_multiple_of(2) { 1.0 } else { -1.0 };
+    let mut vector = Vector3::new(0.0, 0.0, 0.0);
+    vector[axis] = sign;
+    vector
+}
+
+type Faces = HashMap<(u32, [u32; 3]), u32>;
+
+/// Every opaque block face towards a transparent cell as (direction, block) with the block id
+/// as its texture, read straight from the snapshot.
+fn exposed_faces(snapshot: &ChunkSnapshot) -> Faces {
+    let mut faces = HashMap::new();
+    for x in 0..CHUNK_SIZE as i64 {
+        for y in 0..CHUNK_SIZE as i64 {
+            for z in 0..CHUNK_SIZE as i64 {
+                if !snapshot.is_opaque((x, y, z)) {
+                    continue;
+                }
+                let id = snapshot.get(x as usize, y as usize, z as usize).id as u32;
+                for (normal, (dx, dy, dz)) in FACE_NEIGHBOURS.into_iter().enumerate() {
+                    if !snapshot.is_opaque((x + dx, y + dy, z + dz)) {
+                        faces.insert((normal as u32, [x, y, z].map(|c| c as u32)), id);
+                    }
+                }
+            }
+        }
+    }
+    faces
+}
+
+/// Unit faces a quad covers as (direction, block) with its texture, failing on any overlap.
+fn unit_faces(vertices: &[Vertex]) -> Result<Faces, TestCaseError> {
+    let mut faces = HashMap::new();
+    for quad in vertices.chunks(4) {
+        let normal = quad[0].normal();
+        let axis = normal as usize / 2;
+        let tex_id = quad[0].tex_id();
+        prop_assert!(
+            quad.iter()
+                .all(|v| v.normal() == normal && v.tex_id() == tex_id)
+        );
+        let corners: Vec<[u32; 3]> = quad.iter().map(Vertex::corner).collect();
+        let plane = corners[0][axis];
+        prop_assert!(corners.iter().all(|c| c[axis] == plane), "quad not planar");
+        // the face sits on the far side of its block along a positive normal
+        let depth = if normal.is_multiple_of(2) {
+            plane - 1
+        } else {
+            plane
+        };
+
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let range = |a: usize| {
+            let min = corners.iter().map(|c| c[a]).min().unwrap();
+            let max = corners.iter().map(|c| c[a]).max().unwrap();
+            min..max
+        };
+        for cu in range(u) {
+            for cv in range(v) {
+                let mut block = [0; 3];
+                block[axis] = depth;
+                block[u] = cu;
+                block[v] = cv;
+                prop_assert!(
+                    faces.insert((normal, block), tex_id).is_none(),
+                    "quads overlap at {:?} facing {}",
+                    block,
+                    normal
+                );
+            }
+        }
+    }
+    Ok(faces)
+}
+
+fn check_mesh(config: &Config) -> Result<(), TestCaseError> {
+    let world = build_world(config);
+    let snapshot = ChunkSnapshot::capture(&world.chunks[&(0, 0, 0)], &world);
+    check_snapshot(&snapshot).map(|_| ())
+}
+
+/// Checks both meshers against the faces exposed in `snapshot` and returns those faces.
+fn check_snapshot(snapshot: &ChunkSnapshot) -> Result<Faces, TestCaseError> {
+    let vertices = GreedyMesher.mesh(snapshot);
+    let naive = NaiveMesher.mesh(snapshot);
+
+    // drawn through the shared quad index buffer
+    prop_assert_eq!(vertices.len() % 4, 0);
+    prop_assert!(vertices.len() / 4 <= MAX_CHUNK_QUADS);
+    let indices = quad_indices(vertices.len() / 4);
+    prop_assert!(indices.iter().all(|&i| (i as usize) < vertices.len()));
+
+    for triangle in indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| &vertices[triangle[i] as usize]);
+        let facing = (position(b) - position(a)).cross(position(c) - position(a));
+        prop_assert!(
+            facing.dot(normal_vector(a.normal())) > 0.0,
+            "triangle {:?} winds against its normal",
+            triangle
+        );
+    }
+
+    let expected = exposed_faces(snapshot);
+    for (name, mesh) in [("greedy", &vertices), ("naive", &naive)] {
+        let faces = unit_faces(mesh)?;
+        prop_assert_eq!(faces.len(), expected.len(), "{} face count", name);
+        prop_assert!(
+            faces == expected,
+            "{} faces differ from the exposed ones",
+            name
+        );
+    }
+    Ok(expected)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn greedy_covers_exactly_the_exposed_faces(config in config()) {
+        check_mesh(&config)?;
+    }
+}
+
+#[test]
+fn covers_the_chunk_border_without_neighbours() {
+    // solid up to every face of a chunk just above its column's surface window: the chunk
+    // above is known to be empty, the side columns are never estimated and the chunk below
+    // lies inside the window, both of which fall back to solid
+    let mut surface = SurfaceMap::new(1);
+    let z = surface.estimate(0, 0).max_z + 1;
+    let mut world = World::new(1);
+    world.need_to_load = LoadScheduler::new().with_surface(surface);
+    let mut chunk = Chunk::new_empty(Vector3::new(0, 0, z));
+    chunk.blocks.fill(Block::from_id(2));
+    world.chunks.insert((0, 0, z), chunk);
+
+    let snapshot = ChunkSnapshot::capture(&world.chunks[&(0, 0, z)], &world);
+    let faces = check_snapshot(&snapshot).unwrap();
+    assert_eq!(faces.len(), CHUNK_SIZE * CHUNK_SIZE);
+    // +Z is entry 4 of `FACE_NEIGHBOURS`
+    assert!(faces.keys().all(|&(normal, _)| normal == 4));
+}
+
+#[test]
+fn covers_the_chunk_border_next_to_air_and_solid_neighbours() {
+    for neighbour_density in [0.0, 1.0] {
+        check_mesh(&Config {
+            cuboids: vec![Cuboid {
+                min: (0, 0, 0),
+                size: (CHUNK_SIZE, CHUNK_SIZE, CHUNK_SIZE / 2),
+                id: 1,
+            }],
+            noise: 0.0,
+            neighbours: 0b11_1111,
+            neighbour_density,
+            seed: 0,
+        })
+        .unwrap();
+    }
+}
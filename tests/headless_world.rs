@@ -0,0 +1,414 @@
+use cgmath::{Vector3, Vector4};
+use std::time::{Duration, Instant};
+
+use rustcraft::{
+    core::{block::Block, chunk_state::ChunkState, render::frustum_culling::Frustum},
+    world::{
+        World,
+        load_tickets::LoadTicket,
+        mesh_sink::{MeshEvent, NullMeshSink, RecordingMeshSink},
+        observer::Observer,
+        settings::WorldSettings,
+        surface_map::SurfaceMap,
+    },
+};
+
+mod common;
+use common::{loaded_world, observer_at};
+
+/// Sees every chunk reaching past `x` blocks along +x.
+fn frustum_beyond(x: f32) -> Frustum {
+    let mut planes = [Vector4::new(0.0, 0.0, 0.0, 1.0); 6];
+    planes[0] = Vector4::new(1.0, 0.0, 0.0, -x);
+    Frustum { planes }
+}
+
+#[test]
+fn loading_publishes_a_mesh_per_chunk() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let mut sink = RecordingMeshSink::default();
+    let world = loaded_world(settings, observer_at(0.0, &settings), &mut sink);
+
+    assert!(!world.chunks.is_empty());
+    let events = sink.take();
+    assert_eq!(events.len(), world.meshes.len());
+    for key in world.chunks.keys() {
+        assert!(events.contains(&MeshEvent::Updated(*key)));
+    }
+}
+
+#[test]
+fn moving_away_removes_meshes() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = loaded_world(settings, observer_at(0.0, &settings), &mut sink);
+    let loaded: Vec<_> = world.chunks.keys().copied().collect();
+    sink.take();
+
+    world.update(&[observer_at(100_000.0, &settings)], None, &mut sink);
+    let events = sink.take();
+    for key in loaded {
+        assert!(!world.chunks.contains_key(&key));
+        assert!(events.contains(&MeshEvent::Removed(key)));
+    }
+}
+
+#[test]
+fn runs_without_a_sink() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, &mut NullMeshSink);
+    world.finish_loading();
+    world.update(&[observer], None, &mut NullMeshSink);
+    world.finish_meshing(&mut NullMeshSink);
+    assert_eq!(world.meshes.len(), world.chunks.len());
+}
+
+#[test]
+fn tickets_keep_in_flight_chunks_when_the_observer_leaves() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer_at(0.0, &settings)], None, &mut sink);
+    let key = *world.in_flight.keys().next().unwrap();
+    // generating but not integrated yet while the ticket comes in
+    world.add_ticket(LoadTicket::new(Vector3::new(key.0, key.1, key.2), 0));
+
+    world.update(&[observer_at(100_000.0, &settings)], None, &mut sink);
+    assert!(world.in_flight.contains_key(&key) || world.chunks.contains_key(&key));
+    world.finish_loading();
+    assert!(world.chunks.contains_key(&key));
+}
+
+#[test]
+fn removed_tickets_release_their_area() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, &mut NullMeshSink);
+    let id = world.add_ticket(LoadTicket::new(Vector3::new(3_000, 0, 0), 1));
+    world.finish_loading();
+    assert!(world.chunks.contains_key(&(3_000, 0, 0)));
+
+    world.remove_ticket(id);
+    assert!(world.tickets.is_empty());
+    world.finish_unloading(&mut NullMeshSink);
+    assert!(!world.chunks.keys().any(|key| key.0 >= 2_999));
+    // the observer's own area is untouched
+    assert!(world.chunks.keys().any(|key| key.0 == 0));
+}
+
+#[test]
+fn expired_tickets_release_their_area() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, &mut NullMeshSink);
+    let lasting = LoadTicket::new(Vector3::new(-3_000, 0, 0), 0);
+    let expiring =
+        LoadTicket::new(Vector3::new(3_000, 0, 0), 0).with_expiry(Duration::from_secs(60));
+    world.add_ticket(lasting);
+    world.add_ticket(expiring);
+    world.finish_loading();
+
+    world.expire_tickets(Instant::now());
+    assert_eq!(world.tickets.len(), 2, "nothing expired yet");
+    world.expire_tickets(Instant::now() + Duration::from_secs(61));
+    assert_eq!(world.tickets.len(), 1);
+    world.finish_unloading(&mut NullMeshSink);
+    assert!(!world.chunks.contains_key(&(3_000, 0, 0)));
+    assert!(world.chunks.contains_key(&(-3_000, 0, 0)));
+}
+
+#[test]
+fn surface_following_reaches_mountains_above_the_load_box() {
+    let settings = WorldSettings {
+        load_distance_z: 1,
+        ..WorldSettings::default().with_load_distance(1)
+    };
+    // hills rise up to four chunks around column (9, 6)
+    let observer = Observer::new(
+        Vector3::new(9.5 * 32.0, 6.5 * 32.0, 16.0),
+        Vector3::new(1.0, 0.0, 0.0),
+        &settings,
+    );
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, &mut NullMeshSink);
+    world.finish_loading();
+
+    let mut surface = SurfaceMap::new(7);
+    let mut above = 0;
+    for x in 8..=10 {
+        for y in 5..=7 {
+            let window = surface.estimate(x, y);
+            for z in settings.load_distance_z + 1..=window.max_z {
+                assert!(world.chunks.contains_key(&(x, y, z)), "{:?}", (x, y, z));
+                above += 1;
+            }
+        }
+    }
+    assert!(above > 0, "no column reaches above the load box");
+}
+
+#[test]
+fn memory_budget_evicts_least_recently_visible_chunks_first() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = loaded_world(settings, observer, &mut sink);
+    // the x = -1 slab was never seen, x = 0 one frame ago, x = 1 just now
+    world.update(&[observer], Some(&frustum_beyond(1.0)), &mut sink);
+    world.update(&[observer], Some(&frustum_beyond(33.0)), &mut sink);
+
+    let slab_bytes = |world: &World, x: i64| -> usize {
+        world
+            .chunks
+            .keys()
+            .filter(|key| key.0 == x)
+            .map(|key| {
+                world.chunks[key].memory_bytes()
+                    + world.meshes.get(key).map_or(0, |mesh| mesh.memory_bytes())
+            })
+            .sum()
+    };
+    let usage = world.memory_usage().total();
+    let budget = usage - slab_bytes(&world, -1) / 2;
+    world.set_settings(settings.with_memory_budget(Some(budget)));
+    sink.take();
+    world.update(&[observer], None, &mut sink);
+
+    assert!(world.memory_usage().total() <= budget);
+    assert!(!world.evicted.is_empty());
+    assert!(world.evicted.iter().all(|key| key.0 == -1));
+    assert!(!world.over_memory_budget);
+    let events = sink.take();
+    for key in &world.evicted {
+        assert!(!world.chunks.contains_key(key));
+        assert!(events.contains(&MeshEvent::Removed(*key)));
+    }
+}
+
+#[test]
+fn evicted_chunks_come_back_once_visible() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = loaded_world(settings, observer, &mut sink);
+    world.update(&[observer], Some(&frustum_beyond(1.0)), &mut sink);
+    let budget = world.memory_usage().total() / 2;
+    world.set_settings(settings.with_memory_budget(Some(budget)));
+    world.update(&[observer], Some(&frustum_beyond(1.0)), &mut sink);
+    let evicted: Vec<_> = world.evicted.iter().copied().collect();
+    assert!(!evicted.is_empty());
+    assert!(
+        world.over_memory_budget,
+        "the visible chunks alone exceed it"
+    );
+
+    // out of sight they stay away, in view they are queued again
+    world.set_settings(settings);
+    world.update(&[observer], Some(&frustum_beyond(1.0)), &mut sink);
+    world.finish_loading();
+    assert!(evicted.iter().all(|key| !world.chunks.contains_key(key)));
+    world.update(&[observer], Some(&frustum_beyond(-1_000.0)), &mut sink);
+    world.finish_loading();
+    assert!(world.evicted.is_empty());
+    assert!(evicted.iter().all(|key| world.chunks.contains_key(key)));
+}
+
+#[test]
+fn chunks_stay_while_any_observer_needs_them() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let (staying, leaving) = (observer_at(0.0, &settings), observer_at(64.0, &settings));
+    let mut sink = RecordingMeshSink::default();
+    let mut world = World::with_settings(7, settings);
+    world.update(&[staying, leaving], None, &mut sink);
+    world.finish_loading();
+    let shared: Vec<_> = world
+        .chunks
+        .keys()
+        .filter(|key| (-1..=1).contains(&key.0))
+        .copied()
+        .collect();
+    let only_leaving: Vec<_> = world
+        .chunks
+        .keys()
+        .filter(|key| key.0 > 2)
+        .copied()
+        .collect();
+    assert!(!shared.is_empty() && world.chunks.keys().any(|key| key.0 == 3));
+
+    let gone = observer_at(100_000.0, &settings);
+    world.update(&[staying, gone], None, &mut sink);
+    world.finish_unloading(&mut sink);
+    for key in &shared {
+        assert!(world.chunks.contains_key(key), "{key:?} is still in view");
+    }
+    // the staying observer's unload distance ends at x = 2
+    for key in &only_leaving {
+        assert!(
+            !world.chunks.contains_key(key),
+            "{key:?} is only needed by the one that left"
+        );
+    }
+}
+
+#[test]
+fn shrinking_the_distances_unloads_right_away() {
+    let settings = WorldSettings::default().with_load_distance(3);
+    let observer = observer_at(0.0, &settings);
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, &mut NullMeshSink);
+    world.finish_loading();
+    assert!(world.chunks.keys().any(|key| key.0 == 3));
+
+    let smaller = settings.with_load_distance(1);
+    world.set_settings(smaller);
+    // no `update` in between, the old observer is still the one of the last `loader_update`
+    let unload_distance = world.observers[0].unload_distance;
+    assert_eq!(unload_distance, smaller.sanitized().unload_distance);
+    world.finish_unloading(&mut NullMeshSink);
+    assert!(!world.chunks.is_empty());
+    for key in world.chunks.keys() {
+        assert!(key.0 * key.0 + key.1 * key.1 <= unload_distance * unload_distance);
+    }
+    assert!(!world.need_to_load.contains(&(3, 0, 0)));
+}
+
+#[test]
+fn memory_budget_applies_without_a_frustum() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut world = loaded_world(settings, observer, &mut NullMeshSink);
+    let budget = world.memory_usage().total() / 2;
+    world.set_settings(settings.with_memory_budget(Some(budget)));
+    world.update(&[observer], None, &mut NullMeshSink);
+    assert!(world.memory_usage().total() <= budget);
+    assert!(!world.evicted.is_empty());
+
+    // evicted positions are skipped by rescans, they only come back once visible
+    world.set_settings(settings.with_memory_budget(Some(budget)));
+    world.update(&[observer_at(32.0, &settings)], None, &mut NullMeshSink);
+    assert!(
+        world
+            .evicted
+            .iter()
+            .all(|key| { !world.need_to_load.contains(key) && !world.in_flight.contains_key(key) })
+    );
+}
+
+#[test]
+fn tickets_over_loaded_chunks_queue_nothing() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut world = loaded_world(settings, observer, &mut NullMeshSink);
+    world.add_ticket(LoadTicket::new(Vector3::new(0, 0, 0), 1));
+    assert!(world.need_to_load.is_empty());
+    assert_eq!(world.state_counts()[&ChunkState::Queued], 0);
+}
+
+#[test]
+fn observers_just_below_zero_are_in_chunk_minus_one() {
+    let settings = WorldSettings::default();
+    let observer = Observer::new(
+        Vector3::new(-0.5, 31.5, -0.25),
+        Vector3::new(1.0, 0.0, 0.0),
+        &settings,
+    );
+    assert_eq!(observer.chunk(), Vector3::new(-1, 0, -1));
+}
+
+#[test]
+fn meshing_does_not_block_the_update() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let observer = observer_at(0.0, &settings);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = World::with_settings(7, settings);
+    world.update(&[observer], None, &mut sink);
+    world.finish_loading();
+    world.update(&[observer], None, &mut sink);
+
+    // dispatched chunks wait in `Meshing` until their result is swapped in
+    assert!(!world.meshing.is_empty());
+    for key in world.meshing.keys() {
+        assert_eq!(world.chunks[key].state(), ChunkState::Meshing);
+    }
+    world.finish_meshing(&mut sink);
+    assert!(world.meshing.is_empty() && world.dirty_chunks.is_empty());
+    for chunk in world.chunks.values() {
+        assert_eq!(chunk.state(), ChunkState::Ready);
+    }
+}
+
+#[test]
+fn remeshing_keeps_the_previous_mesh_until_replaced() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = loaded_world(settings, observer_at(0.0, &settings), &mut sink);
+    sink.take();
+
+    let (&key, _) = world
+        .meshes
+        .iter()
+        .find(|(_, mesh)| !mesh.vertices.is_empty())
+        .unwrap();
+    let before = world.meshes[&key].vertices.clone();
+    let blocks = world.chunks[&key].blocks;
+    world
+        .chunks
+        .get_mut(&key)
+        .unwrap()
+        .blocks
+        .fill(Block::air());
+    world.dirty_chunks.insert(key);
+    world.update(&[observer_at(0.0, &settings)], None, &mut sink);
+    // dispatched, the emptied chunk still shows its old mesh until the result is swapped in
+    assert_eq!(world.chunks[&key].state(), ChunkState::Meshing);
+    assert!(world.meshing.contains_key(&key));
+    assert_eq!(world.meshes[&key].vertices, before);
+
+    // edited again while the first job is in flight, only the latest result is kept
+    world.chunks.get_mut(&key).unwrap().blocks = blocks;
+    world.dirty_chunks.insert(key);
+    world.update(&[observer_at(0.0, &settings)], None, &mut sink);
+    assert!(world.meshes.contains_key(&key));
+
+    world.finish_meshing(&mut sink);
+    assert_eq!(world.meshes[&key].vertices, before);
+    let updates = sink.take();
+    assert!(updates.contains(&MeshEvent::Updated(key)));
+    assert!(updates.len() <= 2);
+}
+
+#[test]
+fn uploaded_meshes_keep_only_their_metadata() {
+    let settings = WorldSettings::default().with_load_distance(1);
+    let mut sink = RecordingMeshSink::default();
+    let mut world = loaded_world(settings, observer_at(0.0, &settings), &mut sink);
+    let (&key, _) = world
+        .meshes
+        .iter()
+        .find(|(_, mesh)| !mesh.vertices.is_empty())
+        .unwrap();
+
+    let before = world.memory_usage();
+    let mesh = world.meshes.get_mut(&key).unwrap();
+    let (index_count, direction_ranges) = (mesh.index_count, mesh.direction_ranges.clone());
+    // what the renderer does once the vertices are queued for the GPU
+    let vertices = mesh.take_vertices();
+    assert_eq!(mesh.quad_count() as usize, vertices.len() / 4);
+    assert_eq!(mesh.index_count, index_count);
+    assert_eq!(mesh.direction_ranges, direction_ranges);
+    assert!(!mesh.is_dirty && mesh.memory_bytes() == 0);
+    assert!(world.memory_usage().mesh_bytes < before.mesh_bytes);
+
+    // a remesh brings the vertices back under a new version
+    let version = world.meshes[&key].version;
+    world.dirty_chunks.insert(key);
+    world.update(&[observer_at(0.0, &settings)], None, &mut sink);
+    world.finish_meshing(&mut sink);
+    let mesh = &world.meshes[&key];
+    assert!(mesh.is_dirty && mesh.version > version);
+    assert_eq!(mesh.vertices, vertices);
+}
@@ -0,0 +1,109 @@
+use cgmath::Vector3;
+use rustcraft::{
+    core::{
+        block::Block,
+        meshing::{ChunkSnapshot, GreedyMesher, Lod, Vertex},
+    },
+    world::{World, observer::Observer, settings::WorldSettings},
+};
+
+mod common;
+use common::terrain_world;
+
+fn mesh_at(world: &mut World, key: (i64, i64, i64), lod: Lod) -> Vec<Vertex> {
+    world.chunks.get_mut(&key).unwrap().lod = lod;
+    GreedyMesher::build_mesh(&ChunkSnapshot::capture(&world.chunks[&key], world))
+}
+
+/// Chunk of the 3×3×3 block around the origin with the most geometry.
+fn surface_chunk(world: &World) -> (i64, i64, i64) {
+    *world
+        .chunks
+        .keys()
+        .max_by_key(|key| world.chunks[*key].generate_mesh(world).len())
+        .unwrap()
+}
+
+#[test]
+fn coarser_levels_put_faces_on_their_cell_grid() {
+    let mut world = terrain_world();
+    let key = surface_chunk(&world);
+    let full = mesh_at(&mut world, key, Lod::FULL).len();
+    let mut previous = full;
+    for level in 1..=Lod::MAX.level() {
+        let lod = Lod::new(level);
+        // same level on every neighbour, no seams to close
+        for chunk in world.chunks.values_mut() {
+            chunk.lod = lod;
+        }
+        let vertices = mesh_at(&mut world, key, lod);
+        assert!(!vertices.is_empty());
+        assert!(vertices.len() <= previous, "level {level} grew the mesh");
+        // quads may split inside a cell where AO differs, their planes never do
+        for vertex in &vertices {
+            let depth = vertex.corner()[vertex.normal() as usize / 2];
+            assert_eq!(
+                depth as usize % lod.scale(),
+                0,
+                "level {level} off its grid"
+            );
+        }
+        previous = vertices.len();
+    }
+    assert!(previous < full);
+}
+
+#[test]
+fn borders_towards_another_level_are_walled() {
+    let mut world = World::new(1);
+    // solid floor of height 4 in two neighbouring chunks
+    for key in [(0, 0, 0), (1, 0, 0)] {
+        world.load_chunk(key.0, key.1, key.2);
+        let chunk = world.chunks.get_mut(&key).unwrap();
+        chunk.blocks.fill(Block::air());
+        for i in 0..32 * 32 * 4 {
+            chunk.blocks[i] = Block::from_id(1);
+        }
+    }
+    let on_border = |vertices: &[Vertex]| {
+        vertices
+            .iter()
+            .filter(|vertex| vertex.normal() == 0 && vertex.corner()[0] == 32)
+            .count()
+    };
+
+    let same_level = mesh_at(&mut world, (0, 0, 0), Lod::FULL);
+    assert_eq!(on_border(&same_level), 0);
+
+    world.chunks.get_mut(&(1, 0, 0)).unwrap().lod = Lod::new(1);
+    let next_to_coarser = mesh_at(&mut world, (0, 0, 0), Lod::FULL);
+    assert!(on_border(&next_to_coarser) > 0);
+}
+
+#[test]
+fn levels_follow_the_observer_distance() {
+    let settings = WorldSettings::default()
+        .with_load_distance(1)
+        .with_lod_distances([2, 4, 6]);
+    let mut world = World::with_settings(7, settings);
+    let observer = Observer::new(
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(1.0, 0.0, 0.0),
+        &settings,
+    );
+    world.observers = vec![observer];
+    assert_eq!(world.lod_for(&(0, 0, 0)), Lod::FULL);
+    assert_eq!(world.lod_for(&(2, 0, 0)), Lod::FULL);
+    assert_eq!(world.lod_for(&(3, 0, 0)), Lod::new(1));
+    assert_eq!(world.lod_for(&(5, 0, 0)), Lod::new(2));
+    assert_eq!(world.lod_for(&(0, 40, 0)), Lod::MAX);
+
+    world.load_chunk(3, 0, 0);
+    assert_eq!(world.chunks[&(3, 0, 0)].lod, Lod::new(1));
+    // walking up to the chunk brings it back to full resolution
+    let closer = Observer::new(Vector3::new(64.0, 0.0, 0.0), observer.direction, &settings);
+    world.observers = vec![closer];
+    world.update_lods();
+    assert_eq!(world.chunks[&(3, 0, 0)].lod, Lod::FULL);
+    assert!(world.dirty_chunks.contains(&(3, 0, 0)));
+}
@@ -0,0 +1,42 @@
+use cgmath::Vector3;
+use rustcraft::{
+    core::{block::Block, chunk::Chunk},
+    world::surface_map::{ChunkFill, SurfaceMap},
+};
+
+const SEED: u32 = 7;
+
+#[test]
+fn classified_chunks_generate_as_predicted() {
+    let mut surface = SurfaceMap::new(SEED);
+    let (mut empty, mut solid) = (0, 0);
+    // valleys and hills, columns seven chunks apart
+    for (x, y) in (-3..3).flat_map(|x| (-3..3).map(move |y| (x * 7, y * 7))) {
+        let window = surface.estimate(x, y);
+        for z in window.min_z - 2..=window.max_z + 2 {
+            let Some(fill) = surface.fill(&(x, y, z)) else {
+                continue;
+            };
+            let chunk = Chunk::terrain_gen(Vector3::new(x, y, z), SEED);
+            match fill {
+                ChunkFill::Empty => {
+                    empty += 1;
+                    assert!(
+                        chunk.blocks.iter().all(Block::is_transpose),
+                        "{:?} predicted empty",
+                        (x, y, z)
+                    );
+                }
+                ChunkFill::Solid => {
+                    solid += 1;
+                    assert!(
+                        !chunk.blocks.iter().any(Block::is_transpose),
+                        "{:?} predicted solid",
+                        (x, y, z)
+                    );
+                }
+            }
+        }
+    }
+    assert!(empty > 0 && solid > 0);
+}
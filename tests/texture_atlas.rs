@@ -0,0 +1,116 @@
+use image::{Rgba, RgbaImage};
+use rustcraft::core::render::{
+    block_textures::TextureMode,
+    texture_atlas::{AtlasLayout, AtlasRect, MAX_ATLAS_TEXTURES},
+};
+
+fn padded(rect: &AtlasRect, padding: u32) -> (u32, u32, u32, u32) {
+    (
+        rect.x - padding,
+        rect.y - padding,
+        rect.x + rect.width + padding,
+        rect.y + rect.height + padding,
+    )
+}
+
+#[test]
+fn packs_mixed_sizes_without_overlap() {
+    let sizes = [
+        (16, 16),
+        (32, 32),
+        (16, 64),
+        (8, 8),
+        (64, 16),
+        (16, 16),
+        (128, 128),
+    ];
+    let padding = 4;
+    let layout = AtlasLayout::pack(&sizes, padding, 8192).unwrap();
+
+    assert!(layout.width.is_power_of_two() && layout.height.is_power_of_two());
+    assert_eq!(layout.rects.len(), sizes.len());
+    for (i, rect) in layout.rects.iter().enumerate() {
+        assert_eq!((rect.width, rect.height), sizes[i]);
+        let (x0, y0, x1, y1) = padded(rect, padding);
+        assert!(x1 <= layout.width && y1 <= layout.height);
+        for other in &layout.rects[i + 1..] {
+            let (ox0, oy0, ox1, oy1) = padded(other, padding);
+            assert!(x1 <= ox0 || ox1 <= x0 || y1 <= oy0 || oy1 <= y0);
+        }
+    }
+}
+
+#[test]
+fn rejects_what_does_not_fit() {
+    assert!(AtlasLayout::pack(&[(64, 64); 8], 4, 128).is_err());
+    assert!(AtlasLayout::pack(&vec![(1, 1); MAX_ATLAS_TEXTURES + 1], 0, 8192).is_err());
+}
+
+#[test]
+fn uv_rects_are_normalized_and_padding_repeats_edges() {
+    let sizes = [(2, 2), (4, 2)];
+    let layout = AtlasLayout::pack(&sizes, 2, 64).unwrap();
+    let uv = layout.uv_rects();
+    for (rect, uv) in layout.rects.iter().zip(&uv) {
+        assert_eq!(uv[0] * layout.width as f32, rect.x as f32);
+        assert_eq!(uv[3] * layout.height as f32, rect.height as f32);
+    }
+
+    let red = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+    let mut blue = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 255, 255]));
+    blue.put_pixel(0, 0, Rgba([0, 255, 0, 255]));
+    let atlas = layout.compose(&[red, blue]);
+
+    let rect = layout.rects[1];
+    // corner pixel of the texture fills the padding diagonally off that corner
+    assert_eq!(
+        *atlas.get_pixel(rect.x - 2, rect.y - 2),
+        Rgba([0, 255, 0, 255])
+    );
+    assert_eq!(
+        *atlas.get_pixel(rect.x + 5, rect.y + 3),
+        Rgba([0, 0, 255, 255])
+    );
+    let rect = layout.rects[0];
+    assert_eq!(
+        *atlas.get_pixel(rect.x - 1, rect.y + 1),
+        Rgba([255, 0, 0, 255])
+    );
+}
+
+#[test]
+fn auto_mode_falls_back_to_the_atlas() {
+    let png = |width, height| {
+        let mut bytes = Vec::new();
+        RgbaImage::new(width, height)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        bytes
+    };
+    let (small, large) = (png(16, 16), png(32, 32));
+    let same: [&[u8]; 2] = [&small, &small];
+    let mixed: [&[u8]; 2] = [&small, &large];
+
+    assert_eq!(
+        TextureMode::Auto.resolve(&same, 256).unwrap(),
+        TextureMode::Array
+    );
+    assert_eq!(
+        TextureMode::Auto.resolve(&mixed, 256).unwrap(),
+        TextureMode::Atlas
+    );
+    assert_eq!(
+        TextureMode::Auto.resolve(&same, 1).unwrap(),
+        TextureMode::Atlas
+    );
+    assert_eq!(
+        TextureMode::Array.resolve(&mixed, 256).unwrap(),
+        TextureMode::Array
+    );
+
+    let corrupt: [&[u8]; 2] = [&small, b"not a png"];
+    assert!(TextureMode::Auto.resolve(&corrupt, 256).is_err());
+}
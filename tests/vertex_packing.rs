@@ -0,0 +1,13 @@
+use rustcraft::core::meshing::ChunkOrigin;
+
+#[test]
+fn chunk_origins_beyond_i32_are_rejected() {
+    assert_eq!(
+        ChunkOrigin::of_chunk((-1, 2, 0)),
+        Some(ChunkOrigin::new([-32, 64, 0]))
+    );
+    let last = (i32::MAX / 32) as i64;
+    assert!(ChunkOrigin::of_chunk((last, 0, 0)).is_some());
+    assert_eq!(ChunkOrigin::of_chunk((last + 1, 0, 0)), None);
+    assert_eq!(ChunkOrigin::of_chunk((0, 0, i64::MIN)), None);
+}